@@ -12,6 +12,9 @@ You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
 pub mod forwards;
+pub mod fras;
+pub mod futures;
+pub mod fxforwards;
 pub mod options;
 pub mod swaps;
 
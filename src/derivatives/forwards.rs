@@ -7,19 +7,23 @@ License     : MIT <br>
 Maintainer  : Kishaloy Neogi <br>
 Email       : <nkishaloy@yahoo.com>
 
-The module describes the base modules of forward derivatives.
+The module describes the base modules of forward derivatives, with the forward price
+from the spot price and the carry, the mark-to-market value of long and short positions
+before expiry, and the no-arbitrage bounds.
 You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
+use crate::DayCountConvention;
 use chrono::naive::NaiveDate as NDt;
+use serde::{Deserialize, Serialize};
 
 /** Struct for reprenting forward contract using periods
 - rf          = risk-free rate of return per period
 - t_expiry    = Forward expiry date in period
 - fwd_expiry  = Forward rate @ t_exp = S0*(1+r)^t_exp
-- benefit     = Dividends and other benefits - Cost of holding the asset
+- benefit     = PV of Dividends and other benefits - Cost of holding the asset
  */
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Forward {
     pub rf: f64,
     pub t_expiry: f64,
@@ -32,15 +36,214 @@ pub struct Forward {
 - dt_begin    = Forward begin date in NaiveDate
 - dt_expiry   = Forward expiry date in NaiveDate
 - fwd_expiry  = Forward rate @ t_exp = S0*(1+r)^t_exp
-- benefit     = Dividends and other benefits - Cost of holding the asset
+- benefit     = PV of Dividends and other benefits - Cost of holding the asset
+- basis       = Day count convention to convert the dates, US 30/360 if not saved
  */
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct XForward {
     pub rf: f64,
     pub dt_begin: NDt,
     pub dt_expiry: NDt,
     pub fwd_expiry: f64,
     pub benefit: f64,
+    #[serde(default = "default_basis")]
+    pub basis: DayCountConvention,
+}
+
+fn default_basis() -> DayCountConvention {
+    DayCountConvention::US30360
+}
+
+/**
+Enum defining the side of a position
+
+- Long  => Agrees to buy the asset at expiry
+- Short => Agrees to sell the asset at expiry
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Position {
+    Long,
+    Short,
+}
+
+impl Position {
+    /** Sign of the position, +1 for Long and -1 for Short */
+    pub fn sign(&self) -> f64 {
+        match self {
+            Self::Long => 1.0,
+            Self::Short => -1.0,
+        }
+    }
+}
+
+/**
+Carry of holding the underlying asset till the expiry of a forward contract
+
+- income            = PV of the dividends, coupons and other cash income from the asset
+- storage           = PV of the storage and other costs of holding the asset
+- income_yield      = Dividend or other income yield as exponential rate
+- convenience_yield = Convenience yield as exponential rate
+- storage_yield     = Storage cost as exponential rate
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Carry {
+    pub income: f64,
+    pub storage: f64,
+    pub income_yield: f64,
+    pub convenience_yield: f64,
+    pub storage_yield: f64,
+}
+
+impl Carry {
+    /**
+    PV of the net benefit of holding the asset = benefits - costs
+
+    - s0    = Spot price of the asset
+    - t     = Time to expiry in period
+     */
+    pub fn benefit(&self, s0: f64, t: f64) -> f64 {
+        let y = self.income_yield + self.convenience_yield - self.storage_yield;
+        self.income - self.storage + s0 * (1.0 - (-y * t).exp())
+    }
+}
+
+impl Forward {
+    /**
+    New Forward contract at the no-arbitrage forward price
+
+    - s0        = Spot price of the asset
+    - rf        = risk-free rate of return per period
+    - t_expiry  = Forward expiry in period
+    - carry     = Carry of holding the asset
+     */
+    pub fn new(s0: f64, rf: f64, t_expiry: f64, carry: &Carry) -> Forward {
+        let mut fw = Forward {
+            rf,
+            t_expiry,
+            fwd_expiry: 0.0,
+            benefit: carry.benefit(s0, t_expiry),
+        };
+        fw.fwd_expiry = fw.forward_price(s0);
+        fw
+    }
+
+    /**
+    No-arbitrage forward price = (S0 - PV of benefit) * (1 + rf)^t_expiry
+
+    - s0 = Spot price of the asset
+     */
+    pub fn forward_price(&self, s0: f64) -> f64 {
+        crate::fv(self.rf, self.t_expiry, s0 - self.benefit)
+    }
+
+    /**
+    Mark-to-market value of a position at a time before expiry
+    = sign * (St - PV of remaining benefit - PV of forward price)
+
+    - st        = Spot price of the asset at t
+    - t         = time in period at which the position is valued
+    - benefit   = PV at t of the net benefit remaining till expiry
+    - pos       = Long or Short position
+     */
+    pub fn value(&self, st: f64, t: f64, benefit: f64, pos: Position) -> f64 {
+        pos.sign() * (st - benefit - crate::pv(self.rf, self.t_expiry - t, self.fwd_expiry))
+    }
+
+    /**
+    No-arbitrage bounds of the forward price when the rates of lending and borrowing
+    differ and there is a cost of trading the asset
+
+    - s0        = Spot price of the asset
+    - r_lend    = Lending rate per period
+    - r_borrow  = Borrowing rate per period
+    - cost      = Cost of trading the asset at spot
+     */
+    pub fn bounds(&self, s0: f64, r_lend: f64, r_borrow: f64, cost: f64) -> (f64, f64) {
+        (
+            crate::fv(r_lend, self.t_expiry, s0 - cost - self.benefit),
+            crate::fv(r_borrow, self.t_expiry, s0 + cost - self.benefit),
+        )
+    }
+}
+
+impl XForward {
+    /**
+    New dated Forward contract at the no-arbitrage forward price
+
+    - s0        = Spot price of the asset
+    - rf        = risk-free rate of return per period
+    - dt_begin  = Forward begin date
+    - dt_expiry = Forward expiry date
+    - carry     = Carry of holding the asset
+    - basis     = Day count convention
+     */
+    pub fn new(
+        s0: f64,
+        rf: f64,
+        (dt_begin, dt_expiry): (NDt, NDt),
+        carry: &Carry,
+        basis: DayCountConvention,
+    ) -> XForward {
+        let mut fw = XForward {
+            rf,
+            dt_begin,
+            dt_expiry,
+            fwd_expiry: 0.0,
+            benefit: carry.benefit(s0, crate::yearfrac(dt_begin, dt_expiry, basis)),
+            basis,
+        };
+        fw.fwd_expiry = fw.forward_price(s0);
+        fw
+    }
+
+    /** Equivalent Forward contract in periods as per the Day count convention */
+    pub fn to_forward(&self) -> Forward {
+        Forward {
+            rf: self.rf,
+            t_expiry: crate::yearfrac(self.dt_begin, self.dt_expiry, self.basis),
+            fwd_expiry: self.fwd_expiry,
+            benefit: self.benefit,
+        }
+    }
+
+    /**
+    No-arbitrage forward price = (S0 - PV of benefit) * (1 + rf)^t_expiry
+
+    - s0    = Spot price of the asset at dt_begin
+     */
+    pub fn forward_price(&self, s0: f64) -> f64 {
+        self.to_forward().forward_price(s0)
+    }
+
+    /**
+    Mark-to-market value of a position at a date before expiry
+
+    - st        = Spot price of the asset at dt
+    - dt        = Date at which the position is valued
+    - benefit   = PV at dt of the net benefit remaining till expiry
+    - pos       = Long or Short position
+     */
+    pub fn value(&self, st: f64, dt: NDt, benefit: f64, pos: Position) -> f64 {
+        self.to_forward().value(
+            st,
+            crate::yearfrac(self.dt_begin, dt, self.basis),
+            benefit,
+            pos,
+        )
+    }
+
+    /**
+    No-arbitrage bounds of the forward price when the rates of lending and borrowing
+    differ and there is a cost of trading the asset
+
+    - s0        = Spot price of the asset at dt_begin
+    - r_lend    = Lending rate per period
+    - r_borrow  = Borrowing rate per period
+    - cost      = Cost of trading the asset at spot
+     */
+    pub fn bounds(&self, s0: f64, r_lend: f64, r_borrow: f64, cost: f64) -> (f64, f64) {
+        self.to_forward().bounds(s0, r_lend, r_borrow, cost)
+    }
 }
 
 #[cfg(test)]
@@ -51,3 +254,77 @@ mod tests {
         assert_eq!(result, 4);
     }
 }
+
+#[cfg(test)]
+mod forwards_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn forward_contracts() {
+        let fw = Forward::new(
+            100.0,
+            0.05,
+            1.0,
+            &Carry {
+                income: 2.0,
+                ..Default::default()
+            },
+        );
+        assert!(approx(fw.fwd_expiry, 102.9));
+        assert!(approx(fw.value(100.0, 0.0, 2.0, Position::Long), 0.0));
+        let v = fw.value(110.0, 0.5, 0.0, Position::Long);
+        assert!(approx(v, 110.0 - 102.9 / 1.05f64.sqrt()));
+        assert!(approx(fw.value(110.0, 0.5, 0.0, Position::Short), -v));
+        assert!(approx(
+            fw.value(105.0, 1.0, 0.0, Position::Long),
+            105.0 - 102.9
+        ));
+
+        let cm = Forward::new(
+            50.0,
+            0.04,
+            0.5,
+            &Carry {
+                storage: 1.0,
+                convenience_yield: 0.03,
+                ..Default::default()
+            },
+        );
+        assert!(approx(
+            cm.fwd_expiry,
+            (51.0 - 50.0 * (1.0 - (-0.015f64).exp())) * 1.04f64.sqrt()
+        ));
+        let (lo, hi) = fw.bounds(100.0, 0.04, 0.06, 0.5);
+        assert!(lo < fw.fwd_expiry && fw.fwd_expiry < hi);
+        assert!(approx(hi, 98.5 * 1.06));
+
+        let d0 = NDt::from_ymd_opt(2024, 1, 15).unwrap();
+        let d1 = NDt::from_ymd_opt(2024, 7, 15).unwrap();
+        let xf = XForward::new(
+            100.0,
+            0.05,
+            (d0, d1),
+            &Carry::default(),
+            DayCountConvention::ACT360,
+        );
+        let t = 182.0 / 360.0;
+        assert!(approx(xf.fwd_expiry, 100.0 * 1.05f64.powf(t)));
+        assert!(approx(
+            xf.value(
+                101.0,
+                NDt::from_ymd_opt(2024, 4, 15).unwrap(),
+                0.0,
+                Position::Short
+            ),
+            xf.fwd_expiry / 1.05f64.powf(t - 91.0 / 360.0) - 101.0
+        ));
+        let xu = XForward {
+            basis: DayCountConvention::US30360,
+            ..xf
+        };
+        assert!(approx(xu.forward_price(100.0), 100.0 * 1.05f64.sqrt()));
+        let (lo, hi) = xu.bounds(100.0, 0.04, 0.06, 0.0);
+        assert!(approx(lo, 100.0 * 1.04f64.sqrt()) && approx(hi, 100.0 * 1.06f64.sqrt()));
+    }
+}
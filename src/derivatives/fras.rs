@@ -0,0 +1,287 @@
+/*!
+Implement Forward rate agreement modules for the financelib library
+
+Module      : financelib::derivatives::fras <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the Forward rate agreements (FRAs) and their strips, with the fair
+FRA rate from a spot RateCurve, the mark-to-market value and the settlement amount paid
+at the start date as per the market convention. The FRA rates are simple rates with
+accrual as per the day count convention.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::forwards::Position;
+use crate::DayCountConvention;
+use crate::fixedincomes::bonds::curves::CurveInstrument;
+use crate::fixedincomes::bonds::forwardcurves::Compounding;
+use crate::fixedincomes::bonds::rates::{RateCurve, Rates};
+use chrono::{Months, naive::NaiveDate as NDt};
+use serde::{Deserialize, Serialize};
+
+/**
+FRA : struct defining a Forward rate agreement
+
+- notional      = Notional of the FRA
+- fixed_rate    = Fixed FRA rate as simple rate
+- dt_start      = Start date of the FRA period, on which the FRA settles
+- dt_end        = End date of the FRA period
+- basis         = Day count convention of the accrual
+- index         = Reference index of the floating rate, e.g. "EURIBOR3M"
+- position      = Long for the buyer, who pays the fixed rate and receives the index
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FRA {
+    pub notional: f64,
+    pub fixed_rate: f64,
+    pub dt_start: NDt,
+    pub dt_end: NDt,
+    pub basis: DayCountConvention,
+    pub index: String,
+    pub position: Position,
+}
+
+impl FRA {
+    /** Accrual fraction of the FRA period */
+    pub fn tenor(&self) -> f64 {
+        crate::yearfrac(self.dt_start, self.dt_end, self.basis)
+    }
+
+    /**
+    Fair FRA rate as simple rate, from the forward rate of a spot RateCurve whose times are
+    measured from the reference date with the same day count convention
+
+    - rc        = spot RateCurve of the index
+    - dt_ref    = Reference date of the RateCurve
+
+    Returns None if the FRA period has started before the reference date, as the index
+    is then fixed and the FRA is valued by its settlement at the fixing
+     */
+    pub fn fair_rate(&self, rc: &RateCurve, dt_ref: NDt) -> Option<f64> {
+        if dt_ref > self.dt_start {
+            return None;
+        }
+        let t0 = crate::yearfrac(dt_ref, self.dt_start, self.basis);
+        let tau = self.tenor();
+        let fr = Rates::SpotRates { rate: rc.clone() }.forward_rate(t0, tau);
+        Some(Compounding::Simple.rate(rc.compounding().growth(fr, tau), tau))
+    }
+
+    /**
+    Settlement amount paid at the start date once the index is fixed, which is the
+    difference in interest discounted over the FRA period at the fixing rate
+    = sign * notional * (fixing - fixed_rate) * tenor / (1 + fixing * tenor)
+
+    - fixing = Fixing of the index rate for the FRA period
+     */
+    pub fn settlement(&self, fixing: f64) -> f64 {
+        let tau = self.tenor();
+        self.position.sign() * self.notional * (fixing - self.fixed_rate) * tau
+            / (1.0 + fixing * tau)
+    }
+
+    /**
+    Mark-to-market value of the FRA at the reference date of a spot RateCurve, being the
+    settlement at the fair rate discounted from the start date
+
+    - rc        = spot RateCurve, used for both the projection and the discounting
+    - dt_ref    = Reference date of the RateCurve
+
+    Returns None if the FRA period has started before the reference date
+     */
+    pub fn value(&self, rc: &RateCurve, dt_ref: NDt) -> Option<f64> {
+        let t0 = crate::yearfrac(dt_ref, self.dt_start, self.basis);
+        Some(rc.pv(self.settlement(self.fair_rate(rc, dt_ref)?), t0))
+    }
+
+    /**
+    Change in value of the FRA for a rise of 1 bp in the RateCurve
+
+    - rc        = spot RateCurve
+    - dt_ref    = Reference date of the RateCurve
+
+    Returns None if the FRA period has started before the reference date
+     */
+    pub fn dv01(&self, rc: &RateCurve, dt_ref: NDt) -> Option<f64> {
+        Some((self.value(&rc.shift(1e-4), dt_ref)? - self.value(&rc.shift(-1e-4), dt_ref)?) / 2.0)
+    }
+
+    /** FRA quote at the fixed rate as a CurveInstrument for bootstrapping a DiscountCurve */
+    pub fn to_curve_instrument(&self) -> CurveInstrument {
+        CurveInstrument::FRA {
+            dt_start: self.dt_start,
+            dt_end: self.dt_end,
+            rate: self.fixed_rate,
+        }
+    }
+}
+
+/**
+FRAStrip : struct defining a strip of consecutive FRAs, each starting at the end of the
+previous one
+
+- fras = FRAs of the strip in order of their start dates
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FRAStrip {
+    pub fras: Vec<FRA>,
+}
+
+impl FRAStrip {
+    /**
+    New strip of FRAs of equal length from a start date
+
+    - fra       = First FRA of the strip, whose terms are used for all the FRAs
+    - months    = Length of each FRA period in months
+    - rates     = Fixed rate of each FRA
+     */
+    pub fn new(fra: &FRA, months: u32, rates: &[f64]) -> Self {
+        FRAStrip {
+            fras: rates
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| FRA {
+                    fixed_rate: r,
+                    dt_start: fra.dt_start + Months::new(months * i as u32),
+                    dt_end: fra.dt_start + Months::new(months * (i as u32 + 1)),
+                    ..fra.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /**
+    Fair FRA rates of the strip from a spot RateCurve
+
+    - rc        = spot RateCurve of the index
+    - dt_ref    = Reference date of the RateCurve
+
+    Returns None if any FRA of the strip has started, as do the value and DV01 of the strip
+     */
+    pub fn fair_rates(&self, rc: &RateCurve, dt_ref: NDt) -> Option<Vec<f64>> {
+        self.fras.iter().map(|f| f.fair_rate(rc, dt_ref)).collect()
+    }
+
+    /**
+    Strip rate, the simple rate over the whole strip from compounding the fixed rates
+
+    = (prod(1 + r_i * tenor_i) - 1) / sum(tenor_i)
+     */
+    pub fn strip_rate(&self) -> f64 {
+        let g: f64 = self
+            .fras
+            .iter()
+            .map(|f| 1.0 + f.fixed_rate * f.tenor())
+            .product();
+        (g - 1.0) / self.fras.iter().map(|f| f.tenor()).sum::<f64>()
+    }
+
+    /**
+    Mark-to-market value of the strip
+
+    - rc        = spot RateCurve
+    - dt_ref    = Reference date of the RateCurve
+     */
+    pub fn value(&self, rc: &RateCurve, dt_ref: NDt) -> Option<f64> {
+        self.fras.iter().map(|f| f.value(rc, dt_ref)).sum()
+    }
+
+    /**
+    DV01 of each FRA of the strip, used to hedge exposures along the curve
+
+    - rc        = spot RateCurve
+    - dt_ref    = Reference date of the RateCurve
+     */
+    pub fn dv01(&self, rc: &RateCurve, dt_ref: NDt) -> Option<Vec<f64>> {
+        self.fras.iter().map(|f| f.dv01(rc, dt_ref)).collect()
+    }
+
+    /** FRA quotes of the strip as CurveInstruments for bootstrapping a DiscountCurve */
+    pub fn curve_instruments(&self) -> Vec<CurveInstrument> {
+        self.fras.iter().map(|f| f.to_curve_instrument()).collect()
+    }
+}
+
+#[cfg(test)]
+mod fras_fn {
+    use super::*;
+    use crate::approx;
+    use crate::fixedincomes::bonds::curves::DiscountCurve;
+
+    #[test]
+    fn forward_rate_agreements() {
+        let d = |y, m, d| NDt::from_ymd_opt(y, m, d).unwrap();
+        let dt_ref = d(2024, 1, 15);
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0400, 0.0420, 0.0435, 0.0445, 0.0450, 0.0455, 0.0460, 0.0465,
+            ],
+            freq: 4.0,
+        };
+        let fra = FRA {
+            notional: 1e6,
+            fixed_rate: 0.045,
+            dt_start: d(2024, 7, 15),
+            dt_end: d(2024, 10, 15),
+            basis: DayCountConvention::US30360,
+            index: "EURIBOR3M".to_string(),
+            position: Position::Long,
+        };
+        let f = fra.fair_rate(&rc, dt_ref).unwrap();
+        assert!(approx(f, (rc.pv(1.0, 0.5) / rc.pv(1.0, 0.75) - 1.0) / 0.25));
+        assert!(approx(
+            f,
+            Rates::SpotRates { rate: rc.clone() }.forward_rate(0.5, 0.25)
+        ));
+        let fe = fra.fair_rate(&rc.to_exponential(), dt_ref).unwrap();
+        assert!(approx(fe, f));
+        let at_mkt = FRA {
+            fixed_rate: f,
+            ..fra.clone()
+        };
+        assert!(at_mkt.value(&rc, dt_ref).unwrap().abs() < 1e-6);
+        assert!(approx(
+            fra.settlement(0.05),
+            1e6 * 0.005 * 0.25 / (1.0 + 0.05 * 0.25)
+        ));
+        let short = FRA {
+            position: Position::Short,
+            ..fra.clone()
+        };
+        assert!(approx(
+            short.value(&rc, dt_ref).unwrap(),
+            -fra.value(&rc, dt_ref).unwrap()
+        ));
+        assert!(fra.dv01(&rc, dt_ref).unwrap() > 0.0);
+        let started = d(2024, 8, 15);
+        assert_eq!(fra.fair_rate(&rc, started), None);
+        assert_eq!(fra.value(&rc, started), None);
+        assert!(fra.value(&rc, fra.dt_start).is_some());
+
+        let strip = FRAStrip::new(&fra, 3, &[0.044, 0.045, 0.046, 0.047]);
+        assert_eq!(strip.fras[3].dt_end, d(2025, 7, 15));
+        let fs = FRAStrip::new(&fra, 3, &strip.fair_rates(&rc, dt_ref).unwrap());
+        assert!(approx(
+            fs.strip_rate(),
+            (rc.pv(1.0, 0.5) / rc.pv(1.0, 1.5) - 1.0) / 1.0
+        ));
+        assert!(fs.value(&rc, dt_ref).unwrap().abs() < 1e-6);
+        assert!(strip.dv01(&rc, dt_ref).unwrap().iter().all(|&x| x > 0.0));
+        assert_eq!(strip.value(&rc, started), None);
+
+        let mut quotes = vec![CurveInstrument::Deposit {
+            dt_end: d(2024, 7, 15),
+            rate: 0.042,
+        }];
+        quotes.extend(strip.curve_instruments());
+        let dc = DiscountCurve::bootstrap(dt_ref, DayCountConvention::US30360, &quotes).unwrap();
+        assert!(approx(
+            dc.simple_forward_rate(d(2025, 1, 15), d(2025, 4, 15)),
+            0.046
+        ));
+    }
+}
@@ -0,0 +1,447 @@
+/*!
+Implement Interest rate and Bond futures modules for the financelib library
+
+Module      : financelib::derivatives::futures <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the short term interest rate (STIR) futures with the conversion
+between price and rate and the convexity adjustment, and the bond futures with the
+conversion factors as per the exchange rules (CBOT, Eurex), the gross and net basis, the
+implied repo rate, the cheapest-to-deliver bond of the deliverable basket and the
+futures DV01. The bonds are priced as dated XCouponBond per 100 of par.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use crate::DayCountConvention::{self, *};
+use crate::fixedincomes::bonds::XCouponBond;
+use crate::fixedincomes::bonds::conventions::YieldConvention;
+use crate::fixedincomes::bonds::curves::{CurveInstrument, DiscountCurve, futures_convexity};
+use chrono::{Datelike, Months, naive::NaiveDate as NDt};
+use serde::{Deserialize, Serialize};
+
+/** Futures price of a STIR future given the rate = 100 * (1 - rate) */
+pub fn stir_price(rate: f64) -> f64 {
+    100.0 * (1.0 - rate)
+}
+
+/** Rate implied by the price of a STIR future = 1 - price / 100 */
+pub fn stir_rate(price: f64) -> f64 {
+    1.0 - price / 100.0
+}
+
+/**
+StirFuture : struct defining a short term interest rate future, e.g. SOFR or Euribor 3M
+
+- notional  = Notional of the contract
+- price     = Futures price
+- dt_start  = Start date of the rate period
+- dt_end    = End date of the rate period
+- sigma     = Volatility of the short rate for the convexity adjustment
+- basis     = Day count convention
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StirFuture {
+    pub notional: f64,
+    pub price: f64,
+    pub dt_start: NDt,
+    pub dt_end: NDt,
+    pub sigma: f64,
+    pub basis: DayCountConvention,
+}
+
+impl StirFuture {
+    /** Futures rate implied by the price */
+    pub fn rate(&self) -> f64 {
+        stir_rate(self.price)
+    }
+
+    /**
+    Convexity adjustment = futures rate - forward rate, with the times measured from a
+    reference date
+
+    - dt_ref = Reference date
+     */
+    pub fn convexity(&self, dt_ref: NDt) -> f64 {
+        futures_convexity(
+            self.sigma,
+            crate::yearfrac(dt_ref, self.dt_start, self.basis),
+            crate::yearfrac(dt_ref, self.dt_end, self.basis),
+        )
+    }
+
+    /**
+    Forward rate implied by the futures price after the convexity adjustment
+
+    - dt_ref = Reference date
+     */
+    pub fn forward_rate(&self, dt_ref: NDt) -> f64 {
+        self.rate() - self.convexity(dt_ref)
+    }
+
+    /**
+    Fair futures price from the simple forward rate of a DiscountCurve plus the
+    convexity adjustment
+
+    - dc = DiscountCurve
+     */
+    pub fn fair_price(&self, dc: &DiscountCurve) -> f64 {
+        stir_price(dc.simple_forward_rate(self.dt_start, self.dt_end) + self.convexity(dc.dt_ref))
+    }
+
+    /** Change in value of the contract for a fall of 1 bp in the futures rate */
+    pub fn dv01(&self) -> f64 {
+        self.notional * crate::yearfrac(self.dt_start, self.dt_end, self.basis) * 1e-4
+    }
+
+    /** Futures quote as a CurveInstrument for bootstrapping a DiscountCurve */
+    pub fn to_curve_instrument(&self) -> CurveInstrument {
+        CurveInstrument::Future {
+            dt_start: self.dt_start,
+            dt_end: self.dt_end,
+            price: self.price,
+            sigma: self.sigma,
+        }
+    }
+}
+
+/**
+Enum defining the exchange rules of the conversion factors of bond futures
+
+- CBOT  => Price per 1 of par at 6% semi-annual yield on the first day of the delivery month, with the maturity rounded down to whole quarters, rounded to 4 decimals
+- Eurex => Price per 1 of par at 6% yield compounded at the coupon frequency, ACT/ACT, on the delivery date, rounded to 6 decimals
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Exchange {
+    CBOT,
+    Eurex,
+}
+
+/**
+Conversion factor of a deliverable bond as per the exchange rules
+
+- bond          = Deliverable bond
+- dt_delivery   = Delivery date
+- exchange      = Exchange of the futures contract
+
+Returns None if the bond matures on or before the delivery date, or for CBOT if it
+matures within a quarter of the first day of the delivery month
+ */
+pub fn conversion_factor(bond: &XCouponBond, dt_delivery: NDt, exchange: Exchange) -> Option<f64> {
+    if bond.dt_maturity <= dt_delivery {
+        return None;
+    }
+    let round = |x: f64, d: i32| (x * 10f64.powi(d)).round() / 10f64.powi(d);
+    match exchange {
+        Exchange::CBOT => {
+            let dt0 = dt_delivery.with_day(1).unwrap();
+            let months = (3..)
+                .step_by(3)
+                .take_while(|&m| dt0 + Months::new(m) <= bond.dt_maturity)
+                .last()?;
+            let nb = XCouponBond {
+                par: 1.0,
+                c: bond.c,
+                freq: 2.0,
+                dt_maturity: dt0 + Months::new(months),
+            };
+            Some(round(nb.price(dt0, 0.06, YieldConvention::Street)?, 4))
+        }
+        Exchange::Eurex => {
+            let nb = XCouponBond { par: 1.0, ..*bond };
+            Some(round(
+                nb.price(dt_delivery, 0.06, YieldConvention::ICMA)?,
+                6,
+            ))
+        }
+    }
+}
+
+/**
+BondFuture : struct defining a bond futures contract with its deliverable basket
+
+- price         = Futures price per 100 of par
+- dt_delivery   = Delivery date
+- exchange      = Exchange, whose rules give the conversion factors
+- basket        = Deliverable bonds, with par of 100
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondFuture {
+    pub price: f64,
+    pub dt_delivery: NDt,
+    pub exchange: Exchange,
+    pub basket: Vec<XCouponBond>,
+}
+
+impl BondFuture {
+    /**
+    Conversion factor of each bond of the basket
+
+    Returns None if any bond of the basket is not deliverable, as per conversion_factor,
+    as do the other methods on the basket
+     */
+    pub fn conversion_factors(&self) -> Option<Vec<f64>> {
+        self.basket
+            .iter()
+            .map(|b| conversion_factor(b, self.dt_delivery, self.exchange))
+            .collect()
+    }
+
+    /**
+    Invoice price of each bond of the basket = futures price * CF + accrued interest at
+    delivery
+     */
+    pub fn invoice_prices(&self) -> Option<Vec<f64>> {
+        Some(
+            self.basket
+                .iter()
+                .zip(self.conversion_factors()?)
+                .map(|(b, cf)| self.price * cf + b.accrued_interest(self.dt_delivery, ACTACT))
+                .collect(),
+        )
+    }
+
+    /**
+    Gross basis of each bond of the basket = clean price - futures price * CF
+
+    - prices = Clean price of each bond of the basket
+     */
+    pub fn gross_basis(&self, prices: &[f64]) -> Option<Vec<f64>> {
+        Some(
+            prices
+                .iter()
+                .zip(self.conversion_factors()?)
+                .map(|(p, cf)| p - self.price * cf)
+                .collect(),
+        )
+    }
+
+    /** Coupons of a bond paid after settlement till delivery */
+    fn interim_coupons(&self, b: &XCouponBond, settle: NDt) -> f64 {
+        let (_, dts) = b.coupon_dates(settle);
+        dts.iter().filter(|&&d| d <= self.dt_delivery).count() as f64 * b.par * b.c / b.freq
+    }
+
+    /**
+    Implied repo rate of each bond of the basket, the ACT/360 money market return of
+    buying the bond at settlement and delivering it into the futures
+
+    - settle    = Settlement date of the cash purchase
+    - prices    = Clean price of each bond of the basket
+     */
+    pub fn implied_repo(&self, settle: NDt, prices: &[f64]) -> Option<Vec<f64>> {
+        let days = (self.dt_delivery - settle).num_days() as f64;
+        Some(
+            self.basket
+                .iter()
+                .zip(prices)
+                .zip(self.invoice_prices()?)
+                .map(|((b, p), inv)| {
+                    let dirty = p + b.accrued_interest(settle, ACTACT);
+                    (inv + self.interim_coupons(b, settle) - dirty) / dirty * 360.0 / days
+                })
+                .collect(),
+        )
+    }
+
+    /**
+    Net basis of each bond of the basket = gross basis - carry, where the carry is the
+    coupon income less the cost of financing the bond at the repo rate till delivery
+
+    - settle    = Settlement date of the cash purchase
+    - prices    = Clean price of each bond of the basket
+    - repo      = Repo rate, ACT/360
+     */
+    pub fn net_basis(&self, settle: NDt, prices: &[f64], repo: f64) -> Option<Vec<f64>> {
+        let days = (self.dt_delivery - settle).num_days() as f64;
+        Some(
+            self.basket
+                .iter()
+                .zip(prices)
+                .zip(self.invoice_prices()?)
+                .map(|((b, p), inv)| {
+                    let dirty = p + b.accrued_interest(settle, ACTACT);
+                    dirty * (1.0 + repo * days / 360.0) - self.interim_coupons(b, settle) - inv
+                })
+                .collect(),
+        )
+    }
+
+    /**
+    Index of the cheapest-to-deliver bond of the basket, which has the highest implied
+    repo rate
+
+    - settle    = Settlement date of the cash purchase
+    - prices    = Clean price of each bond of the basket
+
+    Returns None if the basket is empty
+     */
+    pub fn cheapest_to_deliver(&self, settle: NDt, prices: &[f64]) -> Option<usize> {
+        let ir = self.implied_repo(settle, prices)?;
+        (0..ir.len()).max_by(|&i, &j| ir[i].total_cmp(&ir[j]))
+    }
+
+    /**
+    DV01 of the futures per 100 of par = DV01 of the cheapest-to-deliver bond / its CF
+
+    - settle    = Settlement date of the cash purchase
+    - prices    = Clean price of each bond of the basket
+    - conv      = Yield convention of the bonds
+     */
+    pub fn dv01(&self, settle: NDt, prices: &[f64], conv: YieldConvention) -> Option<f64> {
+        let i = self.cheapest_to_deliver(settle, prices)?;
+        let b = &self.basket[i];
+        let y = b.ytm(settle, prices[i], conv)?;
+        let dv01 = (b.price(settle, y - 1e-4, conv)? - b.price(settle, y + 1e-4, conv)?) / 2.0;
+        Some(dv01 / self.conversion_factors()?[i])
+    }
+}
+
+#[cfg(test)]
+mod futures_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn interest_rate_futures() {
+        let d = |y, m, d| NDt::from_ymd_opt(y, m, d).unwrap();
+        assert!(approx(stir_price(0.0475), 95.25));
+        assert!(approx(stir_rate(95.25), 0.0475));
+        let dt_ref = d(2024, 1, 15);
+        let sf = StirFuture {
+            notional: 1e6,
+            price: 95.25,
+            dt_start: d(2025, 1, 15),
+            dt_end: d(2025, 4, 15),
+            sigma: 0.012,
+            basis: ACT360,
+        };
+        let t1 = 366.0 / 360.0;
+        let t2 = 456.0 / 360.0;
+        assert!(approx(
+            sf.forward_rate(dt_ref),
+            0.0475 - 0.5 * 0.012 * 0.012 * t1 * t2
+        ));
+        assert!(approx(sf.dv01(), 25.0));
+        let dc = DiscountCurve::bootstrap(
+            dt_ref,
+            ACT360,
+            &[
+                CurveInstrument::Deposit {
+                    dt_end: d(2025, 1, 15),
+                    rate: 0.045,
+                },
+                sf.to_curve_instrument(),
+            ],
+        )
+        .unwrap();
+        assert!(approx(sf.fair_price(&dc), 95.25));
+
+        let cme = |c: f64, n: f64, z: f64| {
+            let v = if z < 7.0 { z } else { 3.0 };
+            let a = 1.0 / 1.03f64.powf(v / 6.0);
+            let b = c / 2.0 * (6.0 - v) / 6.0;
+            let cc = 1.0 / 1.03f64.powf(if z < 7.0 { 2.0 * n } else { 2.0 * n + 1.0 });
+            let dd = c / 0.06 * (1.0 - cc);
+            a * (c / 2.0 + cc + dd) - b
+        };
+        let tn = |c, dt_maturity| XCouponBond {
+            par: 100.0,
+            c,
+            freq: 2.0,
+            dt_maturity,
+        };
+        let dt_delivery = d(2024, 6, 28);
+        [
+            (0.04375, d(2033, 11, 15), 9.0, 3.0),
+            (0.03875, d(2033, 8, 15), 9.0, 0.0),
+            (0.045, d(2034, 2, 15), 9.0, 6.0),
+            (0.04125, d(2032, 11, 15), 8.0, 3.0),
+        ]
+        .iter()
+        .for_each(|&(c, mt, n, z)| {
+            let cf = conversion_factor(&tn(c, mt), dt_delivery, Exchange::CBOT).unwrap();
+            assert!((cf - cme(c, n, z)).abs() < 6e-5);
+        });
+        assert!(approx(
+            conversion_factor(&tn(0.06, d(2033, 8, 15)), dt_delivery, Exchange::CBOT).unwrap(),
+            1.0
+        ));
+        let bund = XCouponBond {
+            par: 100.0,
+            c: 0.06,
+            freq: 1.0,
+            dt_maturity: d(2034, 2, 15),
+        };
+        assert!(approx(
+            conversion_factor(&bund, d(2024, 2, 15), Exchange::Eurex).unwrap(),
+            1.0
+        ));
+        let bund = XCouponBond { c: 0.022, ..bund };
+        let cf = conversion_factor(&bund, d(2024, 3, 11), Exchange::Eurex).unwrap();
+        assert!(cf < 0.75 && cf > 0.7);
+        assert_eq!(
+            conversion_factor(&bund, d(2034, 3, 11), Exchange::Eurex),
+            None
+        );
+
+        let basket = vec![
+            tn(0.04375, d(2033, 11, 15)),
+            tn(0.03875, d(2033, 8, 15)),
+            tn(0.045, d(2034, 2, 15)),
+            tn(0.04125, d(2032, 11, 15)),
+        ];
+        let mut fut = BondFuture {
+            price: 112.0,
+            dt_delivery,
+            exchange: Exchange::CBOT,
+            basket,
+        };
+        // Clean prices at which the bonds earn the given repo rates till delivery, with
+        // the May 15 coupon paid in between on the May / Nov bonds
+        let settle = d(2024, 4, 2);
+        let days = (dt_delivery - settle).num_days() as f64;
+        let repos = [0.050, 0.053, 0.048, 0.051];
+        let interim = [2.1875, 0.0, 0.0, 2.0625];
+        let cfs = fut.conversion_factors().unwrap();
+        let dirty: Vec<f64> = (0..4)
+            .map(|i| {
+                let b = &fut.basket[i];
+                let inv = fut.price * cfs[i] + b.accrued_interest(dt_delivery, ACTACT);
+                (inv + interim[i]) / (1.0 + repos[i] * days / 360.0)
+            })
+            .collect();
+        let prices: Vec<f64> = (0..4)
+            .map(|i| dirty[i] - fut.basket[i].accrued_interest(settle, ACTACT))
+            .collect();
+
+        let ir = fut.implied_repo(settle, &prices).unwrap();
+        ir.iter()
+            .zip(repos)
+            .for_each(|(x, r)| assert!(approx(*x, r)));
+        assert_eq!(fut.cheapest_to_deliver(settle, &prices), Some(1));
+        let nb = fut.net_basis(settle, &prices, 0.053).unwrap();
+        (0..4).for_each(|i| {
+            assert!(approx(nb[i], dirty[i] * (0.053 - repos[i]) * days / 360.0));
+        });
+        assert!(nb[1].abs() < 1e-9 && nb.iter().all(|&x| x >= -1e-9));
+        assert!(approx(
+            fut.gross_basis(&prices).unwrap()[1],
+            prices[1] - fut.price * cfs[1]
+        ));
+        let dv = fut.dv01(settle, &prices, YieldConvention::Street).unwrap();
+        assert!(dv > 0.05 && dv < 0.15);
+
+        let short = tn(0.05, d(2024, 8, 15));
+        assert_eq!(conversion_factor(&short, dt_delivery, Exchange::CBOT), None);
+        assert!(conversion_factor(&tn(0.05, d(2024, 9, 1)), dt_delivery, Exchange::CBOT).is_some());
+        fut.basket.push(short);
+        assert_eq!(fut.conversion_factors(), None);
+        assert_eq!(fut.cheapest_to_deliver(settle, &prices), None);
+        fut.basket.clear();
+        assert_eq!(fut.cheapest_to_deliver(settle, &[]), None);
+    }
+}
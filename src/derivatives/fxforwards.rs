@@ -0,0 +1,527 @@
+/*!
+Implement FX forward and FX swap modules for the financelib library
+
+Module      : financelib::derivatives::fxforwards <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the FX forwards and FX swaps on Currency pairs, with the outright
+forward rate from the spot rate and the discount curves of the two currencies, the
+forward points, and the value of the positions in any reporting currency. The forwards
+on the foreign revenues in the Accounts are used by the treasury to hedge them.
+
+The spot rates are quoted as units of the quote currency per 1 unit of the base currency,
+and the discount curves are taken with the spot date as their reference date. The
+functions needing a spot rate or a discount curve missing in the FXMarket return None.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::forwards::Position;
+use crate::fixedincomes::bonds::curves::DiscountCurve;
+use crate::statements::{Accounts, PlType};
+use crate::{Currency, Period};
+use chrono::naive::NaiveDate as NDt;
+use serde::{Deserialize, Serialize};
+
+/** Size of 1 forward point in the rate */
+pub const PIP: f64 = 1e-4;
+
+/**
+CurrencyPair : struct defining a pair of currencies, e.g. EUR/USD
+
+- base  = Base currency, 1 unit of which is quoted
+- quote = Quote currency, in which the rate is quoted
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrencyPair {
+    pub base: Currency,
+    pub quote: Currency,
+}
+
+impl CurrencyPair {
+    /** Pair with the base and quote currencies swapped */
+    pub fn inverse(&self) -> Self {
+        CurrencyPair {
+            base: self.quote,
+            quote: self.base,
+        }
+    }
+}
+
+/**
+Outright forward rate from the spot rate and the discount factors of the two currencies
+= spot * df_base / df_quote
+
+- spot      = Spot rate
+- df_base   = Discount factor of the base currency to the value date
+- df_quote  = Discount factor of the quote currency to the value date
+ */
+pub fn fx_outright(spot: f64, df_base: f64, df_quote: f64) -> f64 {
+    spot * df_base / df_quote
+}
+
+/**
+Forward points = (outright - spot) / PIP
+
+- spot      = Spot rate
+- outright  = Outright forward rate
+ */
+pub fn forward_points(spot: f64, outright: f64) -> f64 {
+    (outright - spot) / PIP
+}
+
+/**
+FXMarket : struct defining the FX market data
+
+- spots     = Spot rates of the currency pairs
+- curves    = Discount curve of each currency
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FXMarket {
+    pub spots: Vec<(CurrencyPair, f64)>,
+    pub curves: Vec<(Currency, DiscountCurve)>,
+}
+
+impl FXMarket {
+    /** Spot rate quoted directly or as the inverse of the pair */
+    fn quoted(&self, pair: CurrencyPair) -> Option<f64> {
+        self.spots.iter().find_map(|&(p, s)| {
+            if p == pair {
+                Some(s)
+            } else if p == pair.inverse() {
+                Some(1.0 / s)
+            } else {
+                None
+            }
+        })
+    }
+
+    /**
+    Spot rate of a pair, from the quoted spots directly, as inverse or as a cross rate
+    through another currency. None if the pair cannot be found.
+
+    - pair = Currency pair
+     */
+    pub fn spot(&self, pair: CurrencyPair) -> Option<f64> {
+        if pair.base == pair.quote {
+            return Some(1.0);
+        }
+        self.quoted(pair).or_else(|| {
+            self.spots.iter().find_map(|&(p, _)| {
+                [p.base, p.quote].iter().find_map(|&c| {
+                    let leg = |base, quote| self.quoted(CurrencyPair { base, quote });
+                    Some(leg(pair.base, c)? * leg(c, pair.quote)?)
+                })
+            })
+        })
+    }
+
+    /**
+    Convert an amount between currencies at the spot rate
+
+    - amount    = Amount in the currency from
+    - from      = Currency of the amount
+    - to        = Currency to convert to
+     */
+    pub fn convert(&self, amount: f64, from: Currency, to: Currency) -> Option<f64> {
+        Some(
+            amount
+                * self.spot(CurrencyPair {
+                    base: from,
+                    quote: to,
+                })?,
+        )
+    }
+
+    /**
+    Discount factor of a currency at a date. None if the currency has no curve.
+
+    - ccy   = Currency
+    - dt    = date
+     */
+    pub fn df(&self, ccy: Currency, dt: NDt) -> Option<f64> {
+        self.curves
+            .iter()
+            .find(|(c, _)| *c == ccy)
+            .map(|(_, dc)| dc.df(dt))
+    }
+
+    /**
+    Outright forward rate of a pair for a value date
+
+    - pair      = Currency pair
+    - dt_value  = Value date of the forward
+     */
+    pub fn outright(&self, pair: CurrencyPair, dt_value: NDt) -> Option<f64> {
+        Some(fx_outright(
+            self.spot(pair)?,
+            self.df(pair.base, dt_value)?,
+            self.df(pair.quote, dt_value)?,
+        ))
+    }
+}
+
+/**
+FXForward : struct defining an outright FX forward
+
+- pair      = Currency pair
+- notional  = Notional in the base currency
+- rate      = Contracted forward rate
+- dt_value  = Value date on which the currencies are exchanged
+- position  = Long to buy the base currency against the quote currency, Short to sell it
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FXForward {
+    pub pair: CurrencyPair,
+    pub notional: f64,
+    pub rate: f64,
+    pub dt_value: NDt,
+    pub position: Position,
+}
+
+impl FXForward {
+    /**
+    New FX forward at the market outright rate
+
+    - pair      = Currency pair
+    - notional  = Notional in the base currency
+    - dt_value  = Value date
+    - position  = Position in the base currency
+    - mkt       = FXMarket
+     */
+    pub fn at_market(
+        pair: CurrencyPair,
+        notional: f64,
+        dt_value: NDt,
+        position: Position,
+        mkt: &FXMarket,
+    ) -> Option<Self> {
+        Some(FXForward {
+            pair,
+            notional,
+            rate: mkt.outright(pair, dt_value)?,
+            dt_value,
+            position,
+        })
+    }
+
+    /**
+    Forward to hedge the revenue of a period in the Accounts of a foreign unit, selling
+    the currency of the Accounts against the reporting currency at the market outright.
+    None if the period is not in the Accounts or the market data is missing.
+
+    - acc           = Accounts of the foreign unit
+    - period        = Period of the revenue
+    - reporting     = Reporting currency
+    - hedge_ratio   = Fraction of the revenue hedged
+    - dt_value      = Value date on which the revenue is converted
+    - mkt           = FXMarket
+     */
+    pub fn revenue_hedge(
+        acc: &Accounts,
+        period: Period,
+        reporting: Currency,
+        hedge_ratio: f64,
+        dt_value: NDt,
+        mkt: &FXMarket,
+    ) -> Option<Self> {
+        let revenue = *acc
+            .profit_loss
+            .get(&period)?
+            .get(&PlType::Revenue)
+            .unwrap_or(&0.0);
+        Self::at_market(
+            CurrencyPair {
+                base: acc.currency,
+                quote: reporting,
+            },
+            hedge_ratio * revenue,
+            dt_value,
+            Position::Short,
+            mkt,
+        )
+    }
+
+    /**
+    Outright forward rate for the value date
+
+    - mkt = FXMarket
+     */
+    pub fn outright(&self, mkt: &FXMarket) -> Option<f64> {
+        mkt.outright(self.pair, self.dt_value)
+    }
+
+    /**
+    Forward points of the market outright for the value date
+
+    - mkt = FXMarket
+     */
+    pub fn forward_points(&self, mkt: &FXMarket) -> Option<f64> {
+        Some(forward_points(mkt.spot(self.pair)?, self.outright(mkt)?))
+    }
+
+    /**
+    Mark-to-market value in the quote currency
+    = sign * notional * (outright - rate) * df_quote
+
+    - mkt = FXMarket
+     */
+    pub fn value(&self, mkt: &FXMarket) -> Option<f64> {
+        Some(
+            self.position.sign()
+                * self.notional
+                * (self.outright(mkt)? - self.rate)
+                * mkt.df(self.pair.quote, self.dt_value)?,
+        )
+    }
+
+    /**
+    Mark-to-market value in a reporting currency, converted at the spot rate
+
+    - mkt       = FXMarket
+    - reporting = Reporting currency
+     */
+    pub fn value_in(&self, mkt: &FXMarket, reporting: Currency) -> Option<f64> {
+        mkt.convert(self.value(mkt)?, self.pair.quote, reporting)
+    }
+}
+
+/**
+FXSwap : struct defining an FX swap, exchanging the currencies on the near date and
+exchanging them back on the far date
+
+- pair      = Currency pair
+- notional  = Notional in the base currency
+- near_rate = Rate of the near leg
+- far_rate  = Rate of the far leg
+- dt_near   = Value date of the near leg
+- dt_far    = Value date of the far leg
+- position  = Long to buy the base currency on the near leg and sell it on the far leg
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FXSwap {
+    pub pair: CurrencyPair,
+    pub notional: f64,
+    pub near_rate: f64,
+    pub far_rate: f64,
+    pub dt_near: NDt,
+    pub dt_far: NDt,
+    pub position: Position,
+}
+
+impl FXSwap {
+    /**
+    New FX swap with both legs at the market outright rates
+
+    - pair      = Currency pair
+    - notional  = Notional in the base currency
+    - dates     = (near date, far date)
+    - position  = Position in the base currency on the near leg
+    - mkt       = FXMarket
+     */
+    pub fn at_market(
+        pair: CurrencyPair,
+        notional: f64,
+        (dt_near, dt_far): (NDt, NDt),
+        position: Position,
+        mkt: &FXMarket,
+    ) -> Option<Self> {
+        Some(FXSwap {
+            pair,
+            notional,
+            near_rate: mkt.outright(pair, dt_near)?,
+            far_rate: mkt.outright(pair, dt_far)?,
+            dt_near,
+            dt_far,
+            position,
+        })
+    }
+
+    /** Near leg as an FX forward */
+    pub fn near_leg(&self) -> FXForward {
+        FXForward {
+            pair: self.pair,
+            notional: self.notional,
+            rate: self.near_rate,
+            dt_value: self.dt_near,
+            position: self.position,
+        }
+    }
+
+    /** Far leg as an FX forward in the opposite direction */
+    pub fn far_leg(&self) -> FXForward {
+        FXForward {
+            rate: self.far_rate,
+            dt_value: self.dt_far,
+            position: match self.position {
+                Position::Long => Position::Short,
+                Position::Short => Position::Long,
+            },
+            ..self.near_leg()
+        }
+    }
+
+    /** Swap points contracted = (far rate - near rate) / PIP */
+    pub fn swap_points(&self) -> f64 {
+        forward_points(self.near_rate, self.far_rate)
+    }
+
+    /**
+    Market swap points between the near and far dates
+
+    - mkt = FXMarket
+     */
+    pub fn market_points(&self, mkt: &FXMarket) -> Option<f64> {
+        Some(forward_points(
+            mkt.outright(self.pair, self.dt_near)?,
+            mkt.outright(self.pair, self.dt_far)?,
+        ))
+    }
+
+    /**
+    Mark-to-market value in the quote currency, the sum of the near and far legs
+
+    - mkt = FXMarket
+     */
+    pub fn value(&self, mkt: &FXMarket) -> Option<f64> {
+        Some(self.near_leg().value(mkt)? + self.far_leg().value(mkt)?)
+    }
+
+    /**
+    Mark-to-market value in a reporting currency, converted at the spot rate
+
+    - mkt       = FXMarket
+    - reporting = Reporting currency
+     */
+    pub fn value_in(&self, mkt: &FXMarket, reporting: Currency) -> Option<f64> {
+        mkt.convert(self.value(mkt)?, self.pair.quote, reporting)
+    }
+}
+
+#[cfg(test)]
+mod fxforwards_fn {
+    use super::*;
+    use crate::approx;
+    use crate::{Currency::*, DayCountConvention::ACT365};
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    #[test]
+    fn fx_forwards() {
+        let d = |y, m, d| NDt::from_ymd_opt(y, m, d).unwrap();
+        let dt_spot = d(2024, 1, 17);
+        let dc = |z| DiscountCurve::from_zero_rates(dt_spot, ACT365, &[(d(2026, 1, 17), z)]);
+        let eurusd = CurrencyPair {
+            base: EUR,
+            quote: USD,
+        };
+        let mkt = FXMarket {
+            spots: vec![
+                (eurusd, 1.09),
+                (
+                    CurrencyPair {
+                        base: USD,
+                        quote: INR,
+                    },
+                    83.0,
+                ),
+            ],
+            curves: vec![(EUR, dc(0.035)), (USD, dc(0.05)), (INR, dc(0.07))],
+        };
+        assert!(approx(mkt.spot(eurusd.inverse()).unwrap(), 1.0 / 1.09));
+        assert!(approx(
+            mkt.spot(CurrencyPair {
+                base: EUR,
+                quote: INR
+            })
+            .unwrap(),
+            1.09 * 83.0
+        ));
+        assert!(approx(mkt.convert(83.0, INR, USD).unwrap(), 1.0));
+        let gbpusd = CurrencyPair {
+            base: GBP,
+            quote: USD,
+        };
+        assert!(mkt.spot(gbpusd).is_none());
+        assert!(mkt.df(GBP, dt_spot).is_none());
+
+        let dt1 = d(2025, 1, 16);
+        let t = 365.0 / 365.0;
+        let f = mkt.outright(eurusd, dt1).unwrap();
+        assert!(approx(f, 1.09 * (0.015f64 * t).exp()));
+        let fwd = FXForward::at_market(eurusd, 1e6, dt1, Position::Long, &mkt).unwrap();
+        assert!(fwd.value(&mkt).unwrap().abs() < 1e-8);
+        assert!(approx(fwd.forward_points(&mkt).unwrap(), (f - 1.09) / PIP));
+        assert!(FXForward::at_market(gbpusd, 1e6, dt1, Position::Long, &mkt).is_none());
+        let cable = FXForward {
+            pair: gbpusd,
+            ..fwd
+        };
+        assert!(cable.value(&mkt).is_none());
+        assert!(cable.value_in(&mkt, INR).is_none());
+
+        let old = FXForward { rate: 1.08, ..fwd };
+        let v = old.value(&mkt).unwrap();
+        assert!(approx(v, 1e6 * (f - 1.08) * (-0.05f64 * t).exp()));
+        assert!(approx(old.value_in(&mkt, INR).unwrap(), v * 83.0));
+        assert!(approx(old.value_in(&mkt, EUR).unwrap(), v / 1.09));
+        assert!(old.value_in(&mkt, GBP).is_none());
+        let short = FXForward {
+            position: Position::Short,
+            ..old
+        };
+        assert!(approx(short.value(&mkt).unwrap(), -v));
+
+        let sw = FXSwap::at_market(eurusd, 1e6, (dt_spot, dt1), Position::Long, &mkt).unwrap();
+        assert!(approx(sw.near_rate, 1.09));
+        assert!(approx(sw.swap_points(), fwd.forward_points(&mkt).unwrap()));
+        assert!(sw.value(&mkt).unwrap().abs() < 1e-8);
+        let sw_old = FXSwap {
+            far_rate: sw.far_rate + 20.0 * PIP,
+            ..sw
+        };
+        assert!(approx(
+            sw_old.swap_points() - sw_old.market_points(&mkt).unwrap(),
+            20.0
+        ));
+        assert!(approx(
+            sw_old.value(&mkt).unwrap(),
+            1e6 * 20.0 * PIP * mkt.df(USD, dt1).unwrap()
+        ));
+        assert!(approx(
+            sw_old.value_in(&mkt, EUR).unwrap(),
+            sw_old.near_leg().value_in(&mkt, EUR).unwrap()
+                + sw_old.far_leg().value_in(&mkt, EUR).unwrap()
+        ));
+
+        let period = (d(2023, 1, 1), d(2023, 12, 31));
+        let acc = Accounts {
+            currency: EUR,
+            consolidated: false,
+            dates: BTreeSet::from([period.0, period.1]),
+            balance_sheet: BTreeMap::new(),
+            profit_loss: BTreeMap::from([(period, HashMap::from([(PlType::Revenue, 5e6)]))]),
+            cash_flow: BTreeMap::new(),
+            others: BTreeMap::new(),
+        };
+        let hedge = FXForward::revenue_hedge(&acc, period, USD, 0.8, dt1, &mkt).unwrap();
+        assert_eq!(hedge.pair, eurusd);
+        assert!(approx(hedge.notional, 4e6));
+        assert!(approx(hedge.rate, f));
+        let other = (d(2022, 1, 1), d(2022, 12, 31));
+        assert!(FXForward::revenue_hedge(&acc, other, USD, 0.8, dt1, &mkt).is_none());
+        assert!(FXForward::revenue_hedge(&acc, period, GBP, 0.8, dt1, &mkt).is_none());
+
+        // EUR falls against USD, so the short EUR hedge gains
+        let eur_down = FXMarket {
+            spots: vec![(eurusd, 1.00)],
+            ..mkt.clone()
+        };
+        let gain =
+            4e6 * (f - eur_down.outright(eurusd, dt1).unwrap()) * eur_down.df(USD, dt1).unwrap();
+        assert!(gain > 0.0);
+        assert!(approx(hedge.value_in(&eur_down, USD).unwrap(), gain));
+    }
+}
@@ -7,10 +7,100 @@ License     : MIT <br>
 Maintainer  : Kishaloy Neogi <br>
 Email       : <nkishaloy@yahoo.com>
 
-The module describes the base modules of Derivatives like .
+The module describes the base modules of Derivatives like Interest rate swaps, priced
+on a CurveSet with separate discount and projection curves.
 You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
+use crate::fixedincomes::bonds::multicurves::CurveSet;
+use serde::{Deserialize, Serialize};
+
+/**
+InterestRateSwap : struct defining a fixed for floating Interest rate swap
+
+- notional      = Notional of the swap
+- fixed_rate    = Fixed rate paid per period
+- fixed_freq    = Frequency of payment of the fixed leg per period
+- float_freq    = Frequency of payment and reset of the floating leg per period
+- t_life        = Life of the swap
+- spread        = Spread over the index rate paid on the floating leg
+- index         = Index of the projection curve of the floating leg
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestRateSwap {
+    pub notional: f64,
+    pub fixed_rate: f64,
+    pub fixed_freq: f64,
+    pub float_freq: f64,
+    pub t_life: f64,
+    pub spread: f64,
+    pub index: String,
+}
+
+impl InterestRateSwap {
+    /**
+    Annuity of the fixed leg, i.e. PV of 1 unit of fixed rate on the notional. Each
+    function of the swap returns None if a curve it needs is not in the CurveSet.
+
+    - cs = CurveSet
+     */
+    pub fn annuity(&self, cs: &CurveSet) -> Option<f64> {
+        Some(cs.annuity(self.fixed_freq, self.t_life)? * self.notional)
+    }
+
+    /**
+    PV of the fixed leg
+
+    - cs = CurveSet
+     */
+    pub fn fixed_leg(&self, cs: &CurveSet) -> Option<f64> {
+        Some(self.fixed_rate * self.annuity(cs)?)
+    }
+
+    /**
+    PV of the floating leg with the index rates projected from the projection curve
+    and discounted on the discount curve
+
+    - cs = CurveSet
+     */
+    pub fn float_leg(&self, cs: &CurveSet) -> Option<f64> {
+        Some(cs.float_leg(&self.index, self.float_freq, self.t_life, self.spread)? * self.notional)
+    }
+
+    /**
+    Value of the swap to the payer of the fixed rate = float leg - fixed leg
+
+    - cs = CurveSet
+     */
+    pub fn value(&self, cs: &CurveSet) -> Option<f64> {
+        Some(self.float_leg(cs)? - self.fixed_leg(cs)?)
+    }
+
+    /**
+    Par swap rate, i.e. the fixed rate at which the value of the swap is zero
+
+    - cs = CurveSet
+     */
+    pub fn par_rate(&self, cs: &CurveSet) -> Option<f64> {
+        cs.par_swap_rate(&self.index, self.fixed_freq, self.float_freq, self.t_life)
+    }
+
+    /**
+    DV01 of the swap to the payer of the fixed rate for a parallel shift of 1 bp in
+    all the curves of the CurveSet
+
+    - cs = CurveSet
+     */
+    pub fn dv01(&self, cs: &CurveSet) -> Option<f64> {
+        let shift = |s: f64| {
+            let mut cx = cs.clone();
+            cx.curves.values_mut().for_each(|rc| *rc = rc.shift(s));
+            self.value(&cx)
+        };
+        Some((shift(1e-4)? - shift(-1e-4)?) / 2.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -19,3 +109,54 @@ mod tests {
         assert_eq!(result, 4);
     }
 }
+
+#[cfg(test)]
+mod swaps_fn {
+    use super::*;
+    use crate::approx;
+    use crate::fixedincomes::bonds::rates::RateCurve;
+
+    #[test]
+    fn interest_rate_swaps() {
+        let rc = RateCurve::NominalRateCurve {
+            rate: (1..=20).map(|i| 0.02 + 0.0006 * i as f64).collect(),
+            freq: 4.0,
+        };
+        let cs = CurveSet::new("SOFR", rc.clone());
+        let irs = InterestRateSwap {
+            notional: 1e6,
+            fixed_rate: 0.03,
+            fixed_freq: 2.0,
+            float_freq: 4.0,
+            t_life: 5.0,
+            spread: 0.0,
+            index: "SOFR".to_string(),
+        };
+        assert!(approx(
+            irs.float_leg(&cs).unwrap(),
+            1e6 * (1.0 - rc.pv(1.0, 5.0))
+        ));
+        let sw = InterestRateSwap {
+            fixed_rate: irs.par_rate(&cs).unwrap(),
+            ..irs.clone()
+        };
+        assert!(sw.value(&cs).unwrap().abs() < 1e-6);
+        assert!(irs.dv01(&cs).unwrap() > 0.0);
+        assert!(approx(
+            InterestRateSwap {
+                spread: 0.001,
+                ..irs.clone()
+            }
+            .value(&cs)
+            .unwrap()
+                - irs.value(&cs).unwrap(),
+            0.001 * 1e6 / 4.0 * (1..=20).map(|i| rc.pv(1.0, i as f64 / 4.0)).sum::<f64>()
+        ));
+        let term = InterestRateSwap {
+            index: "TERM3M".to_string(),
+            ..irs.clone()
+        };
+        assert!(term.value(&cs).is_none());
+        assert!(term.fixed_leg(&cs).is_some());
+    }
+}
@@ -13,7 +13,10 @@ You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
 pub mod bonds;
+pub mod credits;
 pub mod moneymarkets;
+pub mod mortgages;
+pub mod waterfalls;
 
 #[cfg(test)]
 mod tests {
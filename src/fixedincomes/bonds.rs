@@ -12,8 +12,37 @@ The module describes the base modules of Bonds.
 You may see the github repository at <https://github.com/n-kishaloy/financelib>
  */
 
+pub mod conventions;
+pub mod convertibles;
+pub mod curves;
 pub mod durations;
+pub mod forwardcurves;
+pub mod horizons;
+pub mod interpolations;
+pub mod multicurves;
+pub mod nelsonsiegel;
+pub mod pca;
+pub mod portfolios;
 pub mod rates;
+pub mod scenarios;
+pub mod shortrates;
+pub mod spreads;
+pub mod trees;
+
+use crate::DayCountConvention::{self, *};
+use chrono::{Months, naive::NaiveDate as NDt};
+use conventions::YieldConvention::{self, *};
+use serde::{Deserialize, Serialize};
+
+/**
+Trait for bonds whose cash flows are known in advance
+ */
+pub trait CashFlowSchedule {
+    /**
+    Cash flows of the bond given as vector of (time in period, cash flow)
+     */
+    fn cashflow_schedule(&self) -> Vec<(f64, f64)>;
+}
 
 /**
 CouponBond : struct defining a Coupon bond..
@@ -23,7 +52,7 @@ CouponBond : struct defining a Coupon bond..
 - freq  = Frequency of coupon payment per period
 - T     = Life of the Bond
  */
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct CouponBond {
     pub par: f64,
     pub c: f64,
@@ -104,24 +133,244 @@ impl CouponBond {
     }
 }
 
+impl CashFlowSchedule for CouponBond {
+    fn cashflow_schedule(&self) -> Vec<(f64, f64)> {
+        self.generate_cashflow()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (((i + 1) as f64) / self.freq, c))
+            .collect()
+    }
+}
+
 /**
-CouponBond : struct defining a Coupon bond..
+XCouponBond : struct defining a Coupon bond using dates..
+
+- par           = Par value
+- c             = Coupon rate per year
+- freq          = Frequency of coupon payment per year
+- dt_maturity   = Maturity date of the Bond
+
+The coupon dates are generated backward from the maturity date.
+ */
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct XCouponBond {
+    pub par: f64,
+    pub c: f64,
+    pub freq: f64,
+    pub dt_maturity: NDt,
+}
+
+/**
+Fraction of the coupon period (prev, next) between dt0 and dt1 as per the basis.
+ACTACT uses the actual nos of days in the period as per ICMA.
+ */
+fn period_fraction(dt0: NDt, dt1: NDt, prev: NDt, next: NDt, basis: DayCountConvention) -> f64 {
+    match basis {
+        ACTACT => (dt1 - dt0).num_days() as f64 / (next - prev).num_days() as f64,
+        _ => crate::yearfrac(dt0, dt1, basis) / crate::yearfrac(prev, next, basis),
+    }
+}
+
+impl XCouponBond {
+    /**
+    Coupon dates around a settlement date given as (previous coupon date,
+    vector of remaining coupon dates)
+
+    - settle    = Settlement date
+     */
+    pub fn coupon_dates(&self, settle: NDt) -> (NDt, Vec<NDt>) {
+        let m = (12.0 / self.freq).round() as u32;
+        let mut dts = vec![];
+        let mut k = 0;
+        loop {
+            let dt = self.dt_maturity - Months::new(m * k);
+            if dt <= settle {
+                dts.reverse();
+                return (dt, dts);
+            }
+            dts.push(dt);
+            k += 1;
+        }
+    }
+
+    /**
+    Accrued interest at the settlement date, which is 0 once the bond has matured
+
+    - settle    = Settlement date
+    - basis     = Day count convention for the accrual
+     */
+    pub fn accrued_interest(&self, settle: NDt, basis: DayCountConvention) -> f64 {
+        match self.coupon_dates(settle) {
+            (prev, dts) if !dts.is_empty() => {
+                self.par * self.c / self.freq * period_fraction(prev, settle, prev, dts[0], basis)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /**
+    Full (dirty) price of the bond given a yield
+
+    - settle    = Settlement date
+    - rate      = Yield as per the yield convention
+    - conv      = Yield convention
+
+    Returns None if the bond has matured on or before the settlement date
+     */
+    pub fn dirty_price(&self, settle: NDt, rate: f64, conv: YieldConvention) -> Option<f64> {
+        let (prev, dts) = self.coupon_dates(settle);
+        let n = dts.len();
+        if n == 0 {
+            return None;
+        }
+        let (f, cpn) = (self.freq, self.par * self.c / self.freq);
+        let cf = |k: usize| if k == n - 1 { cpn + self.par } else { cpn };
+        let w = period_fraction(settle, dts[0], prev, dts[0], conv.basis());
+        let pd = (dts[0] - prev).num_days() as f64;
+
+        Some(match conv {
+            Street | ICMA => (0..n)
+                .map(|k| cf(k) / (1.0 + rate / f).powf(w + k as f64))
+                .sum(),
+            AnnualEquivalent => (0..n)
+                .map(|k| cf(k) / (1.0 + rate).powf((w + k as f64) / f))
+                .sum(),
+            USTreasury => {
+                (0..n)
+                    .map(|k| cf(k) / (1.0 + rate / f).powi(k as i32))
+                    .sum::<f64>()
+                    / (1.0 + w * rate / f)
+            }
+            TrueYield => dts
+                .iter()
+                .enumerate()
+                .map(|(k, &d)| {
+                    let lag = (crate::following_business_day(d) - d).num_days() as f64 / pd;
+                    cf(k) / (1.0 + rate / f).powf(w + k as f64 + lag)
+                })
+                .sum(),
+            MoneyMarket => dts
+                .iter()
+                .enumerate()
+                .map(|(k, &d)| cf(k) / (1.0 + rate * (d - settle).num_days() as f64 / 360.0))
+                .sum(),
+            JapaneseSimple => {
+                let t = crate::yearfrac(settle, self.dt_maturity, ACT365);
+                (self.c * self.par + self.par / t) / (rate + 1.0 / t)
+                    + self.accrued_interest(settle, conv.basis())
+            }
+        })
+    }
+
+    /**
+    Clean (flat) price of the bond given a yield
+
+    - settle    = Settlement date
+    - rate      = Yield as per the yield convention
+    - conv      = Yield convention
+
+    Returns None if the bond has matured on or before the settlement date
+     */
+    pub fn price(&self, settle: NDt, rate: f64, conv: YieldConvention) -> Option<f64> {
+        Some(self.dirty_price(settle, rate, conv)? - self.accrued_interest(settle, conv.basis()))
+    }
+
+    /**
+    Yield of the bond given its clean price
+
+    - settle    = Settlement date
+    - price     = Clean price of the bond
+    - conv      = Yield convention
+
+    Returns None if the bond has matured on or before the settlement date or no yield
+    gives the price
+     */
+    pub fn ytm(&self, settle: NDt, price: f64, conv: YieldConvention) -> Option<f64> {
+        self.dirty_price(settle, 0.05, conv)?;
+        crate::newt_raph(|r| self.price(settle, r, conv).unwrap() - price, 0.05, 1e-6)
+    }
+}
+
+/**
+Cash flows of an XCouponBond remaining after the settlement date, with time given in
+years (coupon periods / freq) from the settlement date using ACTACT for the fractional
+first period
+ */
+impl CashFlowSchedule for (XCouponBond, NDt) {
+    fn cashflow_schedule(&self) -> Vec<(f64, f64)> {
+        let (b, settle) = self;
+        let (prev, dts) = b.coupon_dates(*settle);
+        let Some(&next) = dts.first() else {
+            return vec![];
+        };
+        let (n, cpn) = (dts.len(), b.par * b.c / b.freq);
+        let w = period_fraction(*settle, next, prev, next, ACTACT);
+        (0..n)
+            .map(|k| {
+                let c = if k == n - 1 { cpn + b.par } else { cpn };
+                ((w + k as f64) / b.freq, c)
+            })
+            .collect()
+    }
+}
+
+/**
+FloatingRateNotes : struct defining a Floating Rate Note..
 
 - par               = Par value
 - quoted_margin     = Quoted margin payment over the Index rate per period
 - freq              = Frequency of payment per period
-- discount_margin
-- T             = Life of the Bond
+- t_life            = Life of the Bond
+- cap               = Maximum coupon rate (Index + Quoted margin), if capped
+- floor             = Minimum coupon rate (Index + Quoted margin), if floored
+
+A note with both cap and floor is a collared note. The cap and floor default to None
+when absent from saved data.
  */
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct FloatingRateNotes {
     pub par: f64,
     pub quoted_margin: f64,
     pub freq: f64,
     pub t_life: f64,
+    #[serde(default)]
+    pub cap: Option<f64>,
+    #[serde(default)]
+    pub floor: Option<f64>,
 }
 
 impl FloatingRateNotes {
+    /**
+    New Floating Rate Note without cap or floor, which may be set on the fields
+
+    - par           = Par value
+    - quoted_margin = Quoted margin payment over the Index rate per period
+    - freq          = Frequency of payment per period
+    - t_life        = Life of the Bond
+     */
+    pub fn new(par: f64, quoted_margin: f64, freq: f64, t_life: f64) -> Self {
+        FloatingRateNotes {
+            par,
+            quoted_margin,
+            freq,
+            t_life,
+            cap: None,
+            floor: None,
+        }
+    }
+
+    /**
+    Coupon rate for an Index rate fixing after applying the cap and floor
+
+    - index_rate    = Index rate fixed at the reset date
+     */
+    pub fn coupon_rate(&self, index_rate: f64) -> f64 {
+        let c = index_rate + self.quoted_margin;
+        let c = self.floor.map_or(c, |f| c.max(f));
+        self.cap.map_or(c, |f| c.min(f))
+    }
+
     /**
     Price of Floating Rate Note given a discount rate
 
@@ -132,7 +381,7 @@ impl FloatingRateNotes {
             index_rate + discount_margin,
             self.t_life,
             self.freq,
-            -(self.quoted_margin + index_rate) * self.par / self.freq,
+            -self.coupon_rate(index_rate) * self.par / self.freq,
             0.0,
         ) + crate::pvm(
             index_rate + discount_margin,
@@ -152,23 +401,150 @@ impl FloatingRateNotes {
         crate::newt_raph(|x| self.price(index_rate, x) - price, 0.005, 1e-6).unwrap()
     }
 
+    /**
+    Index rates projected for each reset period from the forward rates implied by the
+    Index RateCurve, compounded at the reset frequency of the note whatever the frequency
+    of the curve. All the pricing of the note on curves uses this projection.
+
+    - idx_rc    = Index spot rates given as RateCurve
+     */
+    pub fn projected_index(&self, idx_rc: &rates::RateCurve) -> Vec<f64> {
+        let tenor = 1.0 / self.freq;
+        (0..(self.freq * self.t_life).round() as usize)
+            .map(|i| {
+                let t = i as f64 * tenor;
+                (idx_rc.pv(1.0, t) / idx_rc.pv(1.0, t + tenor) - 1.0) * self.freq
+            })
+            .collect()
+    }
+
+    /**
+    PV at the reset date of coupons paid on the given Index rates, discounted at
+    the Index rates plus the discount margin.
+     */
+    fn pv_index(&self, idx: &[f64], discount_margin: f64) -> f64 {
+        self.pv_projected(idx, idx, discount_margin)
+    }
+
+    /**
+    PV at the reset date of coupons paid on the given Index rates, discounted at the
+    given discount rates plus the discount margin for each reset period.
+     */
+    fn pv_projected(&self, idx: &[f64], disc: &[f64], discount_margin: f64) -> f64 {
+        let n = idx.len();
+        let mut df = 1.0;
+        idx.iter()
+            .zip(disc)
+            .enumerate()
+            .map(|(i, (&r, &d))| {
+                df /= 1.0 + (d + discount_margin) / self.freq;
+                let c = self.coupon_rate(r) * self.par / self.freq;
+                df * if i == n - 1 { c + self.par } else { c }
+            })
+            .sum()
+    }
+
     /**
     Price of Floating Rate Note given a Index RateCurve
 
-    - rc  = Discount rate given as Nominal RateCurve
+    The coupons are projected from the forward rates implied by the Index RateCurve
+    and are discounted at the forward rates plus the discount margin.
+
+    - idx_rc            = Index spot rates given as RateCurve
+    - discount_margin   = Discount margin over the Index rate
      */
-    pub fn price_ratecurve(&self, _idx_rc: &rates::RateCurve, _discount_margin: f64) -> f64 {
-        // TODO: Add imolementation
-        unimplemented!()
+    pub fn price_ratecurve(&self, idx_rc: &rates::RateCurve, discount_margin: f64) -> f64 {
+        self.pv_index(&self.projected_index(idx_rc), discount_margin)
     }
 
     /**
     Discount margin of a Floating Rate Note given a Index RateCurve and a Price
      */
     pub fn discount_margin_ratecurve(&self, price: f64, idx_rc: &rates::RateCurve) -> f64 {
-        // TODO: Check implementation
         crate::newt_raph(|x| self.price_ratecurve(idx_rc, x) - price, 0.005, 1e-6).unwrap()
     }
+
+    /**
+    Price at the last reset date of a Floating Rate Note whose current coupon is
+    already fixed. The later coupons are projected from the Index RateCurve.
+
+    - fixing            = Index rate fixed at the last reset date
+    - idx_rc            = Index spot rates given as RateCurve
+    - discount_margin   = Discount margin over the Index rate
+     */
+    pub fn price_fixing_ratecurve(
+        &self,
+        fixing: f64,
+        idx_rc: &rates::RateCurve,
+        discount_margin: f64,
+    ) -> f64 {
+        let mut idx = self.projected_index(idx_rc);
+        idx[0] = fixing;
+        self.pv_index(&idx, discount_margin)
+    }
+
+    /**
+    Calculates the accrued interest when the purchase is t periods after the last reset.
+
+    - fixing    = Index rate fixed at the last reset date
+    - t         = time since the last reset, so for 26 days in a 360 days year, t = 26/360
+     */
+    pub fn accrued_interest(&self, fixing: f64, t: f64) -> f64 {
+        t * self.coupon_rate(fixing) * self.par
+    }
+
+    /**
+    PV Full when purchase is t periods after the last reset.
+     */
+    pub fn pv_full_ratecurve(
+        &self,
+        fixing: f64,
+        idx_rc: &rates::RateCurve,
+        discount_margin: f64,
+        t: f64,
+    ) -> f64 {
+        self.price_fixing_ratecurve(fixing, idx_rc, discount_margin)
+            * (1.0 + (fixing + discount_margin) / self.freq).powf(t * self.freq)
+    }
+
+    /**
+    PV Flat when purchase is t periods after the last reset.
+     */
+    pub fn pv_flat_ratecurve(
+        &self,
+        fixing: f64,
+        idx_rc: &rates::RateCurve,
+        discount_margin: f64,
+        t: f64,
+    ) -> f64 {
+        self.pv_full_ratecurve(fixing, idx_rc, discount_margin, t)
+            - self.accrued_interest(fixing, t)
+    }
+
+    /**
+    Discount margin given the flat Price when purchase is t periods after the last reset.
+     */
+    pub fn discount_margin_flat_ratecurve(
+        &self,
+        price: f64,
+        fixing: f64,
+        idx_rc: &rates::RateCurve,
+        t: f64,
+    ) -> f64 {
+        crate::newt_raph(
+            |x| self.pv_flat_ratecurve(fixing, idx_rc, x, t) - price,
+            0.005,
+            1e-6,
+        )
+        .unwrap()
+    }
+
+    /**
+    Spread duration, i.e. the % change in Price for a change in the discount margin
+     */
+    pub fn spread_duration(&self, idx_rc: &rates::RateCurve, discount_margin: f64) -> f64 {
+        durations::effective_duration(|x| self.price_ratecurve(idx_rc, x), discount_margin, 1e-4)
+    }
 }
 
 #[cfg(test)]
@@ -206,25 +582,55 @@ mod bonds_fn {
         assert_eq!(cb.pv_flat(0.048, 88.0 / 362.0), 101.40885298021766);
 
         assert_eq!(
-            FloatingRateNotes {
-                par: 100.0,
-                quoted_margin: 0.005,
-                freq: 2.0,
-                t_life: 2.0,
-            }
-            .price(0.0125, 0.004),
+            FloatingRateNotes::new(100.0, 0.005, 2.0, 2.0).price(0.0125, 0.004),
             100.19594209266003
         );
 
         assert_eq!(
-            FloatingRateNotes {
-                par: 100.0,
-                quoted_margin: 0.0075,
-                freq: 4.0,
-                t_life: 5.0
-            }
-            .discount_margin(95.50, 0.011),
+            FloatingRateNotes::new(100.0, 0.0075, 4.0, 5.0).discount_margin(95.50, 0.011),
             0.01718056179887085
         );
     }
+
+    #[test]
+    fn floating_rate_notes() {
+        let rc = rates::RateCurve::NominalRateCurve {
+            rate: vec![0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305],
+            freq: 2.0,
+        };
+        let mut fr = FloatingRateNotes::new(100.0, 0.0, 2.0, 3.0);
+        assert!(crate::approx(fr.price_ratecurve(&rc, 0.0), 100.0));
+
+        fr.quoted_margin = 0.005;
+        let p = fr.price_ratecurve(&rc, 0.008);
+        assert_eq!(p, 99.15313839098704);
+        assert!(crate::approx(fr.discount_margin_ratecurve(p, &rc), 0.008));
+        assert!(crate::approx(
+            fr.spread_duration(&rc, 0.008),
+            2.832541566965897
+        ));
+
+        fr.cap = Some(0.03);
+        let pc = fr.price_ratecurve(&rc, 0.008);
+        fr.floor = Some(0.028);
+        let pl = fr.price_ratecurve(&rc, 0.008);
+        fr.cap = None;
+        let pf = fr.price_ratecurve(&rc, 0.008);
+        assert!(pc < pl && pl < p && p < pf);
+        assert_eq!(fr.coupon_rate(0.02), 0.028);
+        assert!(crate::approx(fr.coupon_rate(0.03), 0.035));
+
+        fr.floor = None;
+        assert!(crate::approx(
+            fr.price_fixing_ratecurve(0.02, &rc, 0.008),
+            fr.price_ratecurve(&rc, 0.008)
+        ));
+        let pf = fr.pv_flat_ratecurve(0.021, &rc, 0.008, 0.2);
+        assert_eq!(pf, 99.20616391464628);
+        assert!(crate::approx(fr.accrued_interest(0.021, 0.2), 0.52));
+        assert!(crate::approx(
+            fr.discount_margin_flat_ratecurve(pf, 0.021, &rc, 0.2),
+            0.008
+        ));
+    }
 }
@@ -0,0 +1,268 @@
+/*!
+Implement Bond Conventions modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::conventions <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the yield conventions and the price quote formats of Bonds as
+used in different markets.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use crate::DayCountConvention::{self, *};
+use serde::{Deserialize, Serialize};
+
+/**
+Enum defining different yield conventions of Bonds
+
+- Street            => Compounding at coupon frequency on scheduled coupon dates, 30/360
+- TrueYield         => Street convention on coupon dates adjusted to the following business day
+- USTreasury        => Simple interest for the fractional first period, then compounding, ACT/ACT
+- ICMA              => Compounding at coupon frequency, ACT/ACT (ICMA) period fractions
+- JapaneseSimple    => (Coupon + (Par - Price) / Years to maturity) / Price, ACT/365
+- MoneyMarket       => Simple interest on each cash flow, ACT/360. Used for short bonds
+- AnnualEquivalent  => Annual compounding, ACT/ACT (ICMA) period fractions
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YieldConvention {
+    Street,
+    TrueYield,
+    USTreasury,
+    ICMA,
+    JapaneseSimple,
+    MoneyMarket,
+    AnnualEquivalent,
+}
+
+impl YieldConvention {
+    /**
+    Day count convention used for the accrual and the fractional period
+     */
+    pub fn basis(&self) -> DayCountConvention {
+        match self {
+            Self::Street => US30360,
+            Self::JapaneseSimple => ACT365,
+            Self::MoneyMarket => ACT360,
+            Self::TrueYield | Self::USTreasury | Self::ICMA | Self::AnnualEquivalent => ACTACT,
+        }
+    }
+}
+
+/**
+Enum defining different formats of price quotes. The prices are given per 100 of par.
+
+- Per100        => Decimal price per 100 of par, e.g. 99.515625
+- PerUnit       => Decimal price per 1 of par, e.g. 0.99515625
+- ThirtySeconds => Price in 32nds, e.g. 99-16+ = 99 + 16.5 / 32
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuoteFormat {
+    Per100,
+    PerUnit,
+    ThirtySeconds,
+}
+
+impl QuoteFormat {
+    /**
+    Format a price given per 100 of par as a quote
+
+    - price = Price per 100 of par
+     */
+    pub fn format(&self, price: f64) -> String {
+        match self {
+            Self::Per100 => format!("{price}"),
+            Self::PerUnit => format!("{}", per_unit(price)),
+            Self::ThirtySeconds => to_32nds(price),
+        }
+    }
+
+    /**
+    Parse a quote to a price given per 100 of par
+
+    - quote = Price quote
+     */
+    pub fn parse(&self, quote: &str) -> Option<f64> {
+        match self {
+            Self::Per100 => quote.trim().parse().ok(),
+            Self::PerUnit => quote.trim().parse().ok().map(per_100),
+            Self::ThirtySeconds => from_32nds(quote),
+        }
+    }
+}
+
+/** Price per 1 of par from price per 100 of par */
+pub fn per_unit(price: f64) -> f64 {
+    price / 100.0
+}
+
+/** Price per 100 of par from price per 1 of par */
+pub fn per_100(price: f64) -> f64 {
+    price * 100.0
+}
+
+/**
+Price in 32nds rounded to the nearest 64th, with + denoting half of a 32nd
+
+- price = Price per 100 of par, e.g. 99.515625 => 99-16+
+ */
+pub fn to_32nds(price: f64) -> String {
+    let mut whole = price.floor();
+    let mut n64 = ((price - whole) * 64.0).round() as i64;
+    if n64 == 64 {
+        (whole, n64) = (whole + 1.0, 0);
+    }
+    format!(
+        "{}-{:02}{}",
+        whole,
+        n64 / 2,
+        if n64 % 2 == 1 { "+" } else { "" }
+    )
+}
+
+/**
+Price per 100 of par from a quote in 32nds. Supports the formats 99-16, 99-16+
+and 99-162, where the third digit gives the 8ths of a 32nd.
+
+- quote = Price quote in 32nds
+
+Returns None if the quote is malformed or the 32nds or 8ths are out of range
+ */
+pub fn from_32nds(quote: &str) -> Option<f64> {
+    let (whole, frac) = quote.trim().split_once('-')?;
+    let whole: f64 = whole.parse().ok()?;
+    let (frac, half) = match frac.strip_suffix('+') {
+        Some(x) => (x, 0.5),
+        None => (frac, 0.0),
+    };
+    let n32: u32 = frac.get(..2)?.parse().ok().filter(|&x| x < 32)?;
+    let n256: f64 = match frac.get(2..) {
+        Some("") | None => 0.0,
+        Some(x) if half == 0.0 => x.parse::<u32>().ok().filter(|&x| x < 8)? as f64 / 8.0,
+        _ => return None,
+    };
+    Some(whole + (n32 as f64 + half + n256) / 32.0)
+}
+
+#[cfg(test)]
+mod conventions_fn {
+    use super::super::{CouponBond, XCouponBond};
+    use super::YieldConvention::*;
+    use super::*;
+    use crate::approx;
+    use chrono::naive::NaiveDate as NDt;
+
+    #[test]
+    fn yield_conventions() {
+        let xb = XCouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            dt_maturity: NDt::from_ymd_opt(2029, 6, 15).unwrap(),
+        };
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let settle = NDt::from_ymd_opt(2024, 6, 15).unwrap();
+        assert!(approx(
+            xb.price(settle, 0.06, Street).unwrap(),
+            cb.price(0.06)
+        ));
+        assert!(approx(xb.ytm(settle, cb.price(0.06), ICMA).unwrap(), 0.06));
+
+        let settle = NDt::from_ymd_opt(2024, 8, 20).unwrap();
+        assert!(approx(
+            xb.accrued_interest(settle, US30360),
+            2.5 * 65.0 / 180.0
+        ));
+        assert!(approx(
+            xb.accrued_interest(settle, ACTACT),
+            2.5 * 66.0 / 183.0
+        ));
+
+        [
+            Street,
+            TrueYield,
+            USTreasury,
+            ICMA,
+            JapaneseSimple,
+            MoneyMarket,
+            AnnualEquivalent,
+        ]
+        .iter()
+        .for_each(|&cv| {
+            assert!(approx(
+                xb.ytm(settle, xb.price(settle, 0.055, cv).unwrap(), cv)
+                    .unwrap(),
+                0.055
+            ))
+        });
+
+        let p = 97.25;
+        let ytm = |cv| xb.ytm(settle, p, cv).unwrap();
+        let (ys, ya) = (ytm(Street), ytm(AnnualEquivalent));
+        assert!(approx(ya, crate::nom_eff_rate(ytm(ICMA), 2.0)));
+        assert!(ya > ys);
+        // 2029-06-15 is a Friday, while 2027-12-15 is a Wednesday
+        assert!(ytm(TrueYield) <= ytm(ICMA));
+        let yj = ytm(JapaneseSimple);
+        let t = crate::yearfrac(settle, xb.dt_maturity, ACT365);
+        assert!(approx(yj, (5.0 + (100.0 - p) / t) / p));
+
+        let sb = XCouponBond {
+            dt_maturity: NDt::from_ymd_opt(2024, 12, 15).unwrap(),
+            ..xb
+        };
+        let pm = sb.dirty_price(settle, 0.05, MoneyMarket).unwrap();
+        assert!(approx(pm, 102.5 / (1.0 + 0.05 * 117.0 / 360.0)));
+        assert!(approx(
+            sb.dirty_price(settle, 0.05, USTreasury).unwrap(),
+            102.5 / (1.0 + 0.05 / 2.0 * 117.0 / 183.0)
+        ));
+        let lb = XCouponBond {
+            dt_maturity: NDt::from_ymd_opt(2025, 6, 15).unwrap(),
+            ..xb
+        };
+        assert!(approx(
+            lb.dirty_price(settle, 0.05, USTreasury).unwrap(),
+            (2.5 + 2.5 / 1.025 + 102.5 / 1.025 / 1.025) / (1.0 + 0.05 / 2.0 * 117.0 / 183.0)
+        ));
+        assert!(
+            lb.dirty_price(settle, 0.05, USTreasury).unwrap()
+                < lb.dirty_price(settle, 0.05, ICMA).unwrap()
+        );
+
+        [sb.dt_maturity, NDt::from_ymd_opt(2025, 1, 10).unwrap()]
+            .iter()
+            .for_each(|&d| {
+                assert_eq!(sb.accrued_interest(d, ACTACT), 0.0);
+                assert_eq!(sb.dirty_price(d, 0.05, Street), None);
+                assert_eq!(sb.price(d, 0.05, Street), None);
+                assert_eq!(sb.ytm(d, 100.0, Street), None);
+            });
+    }
+
+    #[test]
+    fn quote_formats() {
+        assert_eq!(to_32nds(99.515625), "99-16+");
+        assert_eq!(to_32nds(101.0625), "101-02");
+        assert_eq!(to_32nds(99.99), "99-31+");
+        assert_eq!(to_32nds(99.995), "100-00");
+        assert_eq!(from_32nds("99-16+"), Some(99.515625));
+        assert_eq!(from_32nds("99-162"), Some(99.0 + 16.25 / 32.0));
+        assert_eq!(from_32nds("99-16"), Some(99.5));
+        assert_eq!(from_32nds("99.5"), None);
+        assert_eq!(from_32nds("99-45"), None);
+        assert_eq!(from_32nds("99-168"), None);
+        assert_eq!(QuoteFormat::PerUnit.format(99.5), "0.995");
+        assert!(approx(QuoteFormat::PerUnit.parse("0.995").unwrap(), 99.5));
+        assert_eq!(QuoteFormat::ThirtySeconds.parse("99-16+"), Some(99.515625));
+        assert_eq!(QuoteFormat::Per100.parse(" 99.5 "), Some(99.5));
+    }
+}
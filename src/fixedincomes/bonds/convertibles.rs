@@ -0,0 +1,254 @@
+/*!
+Implement Convertible Bonds modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::convertibles <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the valuation of Convertible bonds using a binomial equity tree
+with credit adjusted discounting as per Tsiveriotis-Fernandes. The value at each node
+is split into a cash only part, discounted at the risky rate (risk-free + credit spread),
+and an equity part, discounted at the risk-free rate.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::CouponBond;
+use serde::{Deserialize, Serialize};
+
+/**
+ConvertibleBond : struct defining a Convertible bond
+
+- bond              = underlying CouponBond
+- conversion_ratio  = Nos of shares received on conversion of 1 bond
+- call_schedule     = Vector of (time, call price), callable from the time till the next entry
+- put_schedule      = Vector of (time, put price), putable only on the time
+- credit_spread     = Credit spread of the issuer as exponential rate
+- dividend_yield    = Dividend yield of the equity as exponential rate
+
+The call and put prices are clean prices, with the coupon due at the time being
+received in addition.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertibleBond {
+    pub bond: CouponBond,
+    pub conversion_ratio: f64,
+    pub call_schedule: Vec<(f64, f64)>,
+    pub put_schedule: Vec<(f64, f64)>,
+    pub credit_spread: f64,
+    pub dividend_yield: f64,
+}
+
+/**
+Valuation of a Convertible bond
+
+- price                 = Value of the Convertible bond
+- bond_floor            = Value of the bond without the conversion option
+- parity                = Conversion value = conversion ratio * share price
+- conversion_premium    = price / parity - 1
+- delta                 = Change in price per unit change in share price
+- gamma                 = Change in delta per unit change in share price
+ */
+#[derive(Debug, Copy, Clone)]
+pub struct ConvertibleValuation {
+    pub price: f64,
+    pub bond_floor: f64,
+    pub parity: f64,
+    pub conversion_premium: f64,
+    pub delta: f64,
+    pub gamma: f64,
+}
+
+impl ConvertibleBond {
+    /**
+    Value of the straight bond discounted at the risky rate (risk-free + credit spread)
+
+    - rf    = risk-free rate given as exponential rate
+     */
+    pub fn bond_floor(&self, rf: f64) -> f64 {
+        self.bond
+            .generate_cashflow()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| crate::pvc(rf + self.credit_spread, (i + 1) as f64 / self.bond.freq, c))
+            .sum()
+    }
+
+    /**
+    Conversion value of the bond
+
+    - s0    = share price
+     */
+    pub fn parity(&self, s0: f64) -> f64 {
+        self.conversion_ratio * s0
+    }
+
+    /** Call price applicable at time t, if the bond is callable */
+    fn call_price(&self, t: f64) -> Option<f64> {
+        self.call_schedule
+            .iter()
+            .filter(|&&(tc, _)| tc <= t + 1e-9)
+            .max_by(|x, y| x.0.total_cmp(&y.0))
+            .map(|&(_, p)| p)
+    }
+
+    /** Put price applicable at the tree step around time t, if the bond is putable */
+    fn put_price(&self, t: f64, dt: f64) -> Option<f64> {
+        self.put_schedule
+            .iter()
+            .find(|&&(tp, _)| (tp - t).abs() < dt / 2.0)
+            .map(|&(_, p)| p)
+    }
+
+    /**
+    Price of the Convertible bond
+
+    - s0    = share price
+    - sigma = volatility of the share price
+    - rf    = risk-free rate given as exponential rate
+    - steps = nos of steps in the binomial tree, at least the nos of coupons
+
+    Returns None if the tree can not be built, see valuation
+     */
+    pub fn price(&self, s0: f64, sigma: f64, rf: f64, steps: usize) -> Option<f64> {
+        Some(self.valuation(s0, sigma, rf, steps)?.price)
+    }
+
+    /**
+    Valuation of the Convertible bond using a binomial tree of the share price
+
+    - s0    = share price
+    - sigma = volatility of the share price
+    - rf    = risk-free rate given as exponential rate
+    - steps = nos of steps in the binomial tree, preferably a multiple of coupons
+
+    Returns None if steps is less than 2, as the delta and gamma need the first 2 levels
+    of the tree, or less than the nos of coupons, so that each coupon falls on its own
+    step, or if the risk-neutral probability is outside [0, 1]
+     */
+    pub fn valuation(
+        &self,
+        s0: f64,
+        sigma: f64,
+        rf: f64,
+        steps: usize,
+    ) -> Option<ConvertibleValuation> {
+        let cb = &self.bond;
+        let n_cpn = (cb.freq * cb.t_life) as usize;
+        if steps < 2 || steps < n_cpn {
+            return None;
+        }
+        let dt = cb.t_life / steps as f64;
+        let (u, d) = ((sigma * dt.sqrt()).exp(), (-sigma * dt.sqrt()).exp());
+        let p = (((rf - self.dividend_yield) * dt).exp() - d) / (u - d);
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+        let (df_rf, df_rk) = ((-rf * dt).exp(), (-(rf + self.credit_spread) * dt).exp());
+
+        let mut cpn = vec![0.0; steps + 1];
+        (1..=n_cpn).for_each(|k| {
+            cpn[((k as f64 / cb.freq) / dt).round() as usize] += cb.par * cb.c / cb.freq
+        });
+        cpn[steps] += cb.par;
+
+        let node = |i: usize, j: usize, vc: f64, bc: f64| -> (f64, f64) {
+            let t = i as f64 * dt;
+            let conv = self.conversion_ratio * s0 * u.powi(j as i32) * d.powi((i - j) as i32);
+            let (mut v, mut b) = (vc + cpn[i], bc + cpn[i]);
+            if let Some(pp) = self.put_price(t, dt).filter(|&pp| pp + cpn[i] > v) {
+                (v, b) = (pp + cpn[i], pp + cpn[i]);
+            }
+            if let Some(pc) = self.call_price(t).filter(|&pc| v > pc + cpn[i]) {
+                (v, b) = (pc + cpn[i], pc + cpn[i]);
+            }
+            if conv > v { (conv, 0.0) } else { (v, b) }
+        };
+
+        let (mut v, mut b): (Vec<f64>, Vec<f64>) =
+            (0..=steps).map(|j| node(steps, j, 0.0, 0.0)).unzip();
+        let (mut v1, mut v2) = (vec![], vec![]);
+
+        for i in (0..steps).rev() {
+            match i + 1 {
+                2 => v2 = v.clone(),
+                1 => v1 = v.clone(),
+                _ => (),
+            }
+            (v, b) = (0..=i)
+                .map(|j| {
+                    let bc = df_rk * (p * b[j + 1] + (1.0 - p) * b[j]);
+                    let ec = df_rf * (p * (v[j + 1] - b[j + 1]) + (1.0 - p) * (v[j] - b[j]));
+                    node(i, j, bc + ec, bc)
+                })
+                .unzip();
+        }
+
+        let price = v[0];
+        let parity = self.parity(s0);
+        let delta = (v1[1] - v1[0]) / (s0 * (u - d));
+        let (su, sm, sd) = (s0 * u * u, s0, s0 * d * d);
+        let gamma = ((v2[2] - v2[1]) / (su - sm) - (v2[1] - v2[0]) / (sm - sd)) / ((su - sd) / 2.0);
+
+        Some(ConvertibleValuation {
+            price,
+            bond_floor: self.bond_floor(rf),
+            parity,
+            conversion_premium: price / parity - 1.0,
+            delta,
+            gamma,
+        })
+    }
+}
+
+#[cfg(test)]
+mod convertibles_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn convertible_bonds() {
+        let mut cv = ConvertibleBond {
+            bond: CouponBond {
+                par: 100.0,
+                c: 0.04,
+                freq: 2.0,
+                t_life: 5.0,
+            },
+            conversion_ratio: 0.0,
+            call_schedule: vec![],
+            put_schedule: vec![],
+            credit_spread: 0.02,
+            dividend_yield: 0.01,
+        };
+
+        let vl = cv.valuation(50.0, 0.3, 0.03, 100).unwrap();
+        assert!(approx(vl.price, vl.bond_floor));
+        assert!(approx(vl.delta, 0.0));
+
+        cv.conversion_ratio = 2.0;
+        let vl = cv.valuation(50.0, 0.3, 0.03, 100).unwrap();
+        assert!(approx(vl.price, 126.71407692847211));
+        assert!(approx(vl.conversion_premium, 0.2671407692847212));
+        assert!(approx(vl.delta, 1.3911913292622862));
+        assert!(vl.price > vl.bond_floor && vl.price > vl.parity);
+        assert!(vl.delta > 0.0 && vl.delta < cv.conversion_ratio);
+        assert!(vl.gamma > 0.0);
+        assert!(cv.valuation(50.0, 0.3, 0.03, 1).is_none());
+        assert!(cv.price(50.0, 0.3, 0.03, 0).is_none());
+        assert!(cv.valuation(50.0, 0.3, 0.03, 10).is_some());
+        assert!(cv.valuation(50.0, 0.3, 0.03, 9).is_none());
+        cv.dividend_yield = 0.5;
+        assert!(cv.valuation(50.0, 0.001, 0.03, 100).is_none());
+        cv.dividend_yield = 0.01;
+
+        cv.call_schedule = vec![(2.0, 102.0)];
+        let pc = cv.price(50.0, 0.3, 0.03, 100).unwrap();
+        cv.put_schedule = vec![(3.0, 100.0)];
+        let pp = cv.price(50.0, 0.3, 0.03, 100).unwrap();
+        assert!(approx(pc, 116.44649866005744));
+        assert!(pc < vl.price && pc < pp);
+    }
+}
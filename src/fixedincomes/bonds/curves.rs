@@ -0,0 +1,552 @@
+/*!
+Implement Discount Curve modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::curves <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the Discount curves with non-uniform pillars given by dates and
+anchored at a reference date, along with the discount factor, zero rate and forward rate
+queries by date and their bootstrapping from a mixed set of market quotes like deposits,
+FRAs, futures, par swaps and coupon bond prices.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use super::{CashFlowSchedule, CouponBond, XCouponBond};
+use crate::DayCountConvention;
+use chrono::{Days, Months, naive::NaiveDate as NDt};
+use serde::{Deserialize, Serialize};
+
+/**
+Date at a tenor from a date
+
+- dt    = Start date
+- tenor = Tenor given as nos followed by D, W, M or Y, e.g. 1W, 3M, 10Y
+ */
+pub fn tenor_date(dt: NDt, tenor: &str) -> Option<NDt> {
+    let tenor = tenor.trim().to_uppercase();
+    let (n, unit) = tenor.split_at(tenor.len().checked_sub(1)?);
+    let n: u32 = n.parse().ok()?;
+    match unit {
+        "D" => dt.checked_add_days(Days::new(n as u64)),
+        "W" => dt.checked_add_days(Days::new(7 * n as u64)),
+        "M" => dt.checked_add_months(Months::new(n)),
+        "Y" => dt.checked_add_months(Months::new(12 * n)),
+        _ => None,
+    }
+}
+
+/**
+DiscountCurve : struct defining a curve of discount factors with pillars given by dates
+
+- dt_ref    = Reference date of the curve, where the discount factor is 1
+- basis     = Day count convention to convert dates to time
+- pillars   = Vector of (date, discount factor) in ascending order of dates
+
+The discount factors are interpolated log-linearly in time, i.e. flat forward between
+the pillars, and extrapolated with the zero rate of the last pillar.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscountCurve {
+    pub dt_ref: NDt,
+    pub basis: DayCountConvention,
+    pub pillars: Vec<(NDt, f64)>,
+}
+
+impl DiscountCurve {
+    /**
+    Time from the reference date to a date as per the basis
+
+    - dt = date
+     */
+    pub fn time(&self, dt: NDt) -> f64 {
+        crate::yearfrac(self.dt_ref, dt, self.basis)
+    }
+
+    /**
+    Curve from continuously compounded zero rates at the pillar dates
+
+    - dt_ref    = Reference date of the curve
+    - basis     = Day count convention to convert dates to time
+    - zeros     = Vector of (date, zero rate) in ascending order of dates
+     */
+    pub fn from_zero_rates(dt_ref: NDt, basis: DayCountConvention, zeros: &[(NDt, f64)]) -> Self {
+        let t = |d| crate::yearfrac(dt_ref, d, basis);
+        DiscountCurve {
+            dt_ref,
+            basis,
+            pillars: zeros.iter().map(|&(d, z)| (d, (-z * t(d)).exp())).collect(),
+        }
+    }
+
+    /**
+    Curve from continuously compounded zero rates at the pillar tenors
+
+    - dt_ref    = Reference date of the curve
+    - basis     = Day count convention to convert dates to time
+    - zeros     = Vector of (tenor, zero rate) in ascending order of tenors, e.g. ("3M", 0.05)
+
+    Returns None if any tenor cannot be parsed
+     */
+    pub fn from_tenors(
+        dt_ref: NDt,
+        basis: DayCountConvention,
+        zeros: &[(&str, f64)],
+    ) -> Option<Self> {
+        let zx = zeros
+            .iter()
+            .map(|&(tn, z)| Some((tenor_date(dt_ref, tn)?, z)))
+            .collect::<Option<Vec<(NDt, f64)>>>()?;
+        Some(Self::from_zero_rates(dt_ref, basis, &zx))
+    }
+
+    /**
+    Discount factor at a date
+
+    - dt = date
+     */
+    pub fn df(&self, dt: NDt) -> f64 {
+        self.df_time(self.time(dt))
+    }
+
+    /**
+    Discount factor at a time from the reference date
+
+    - t = time in years as per the basis
+     */
+    pub fn df_time(&self, t: f64) -> f64 {
+        let (mut t0, mut l0) = (0.0, 0.0);
+        for &(d, df) in &self.pillars {
+            let (t1, l1) = (self.time(d), df.ln());
+            if t <= t1 {
+                return if t1 - t0 < 1e-12 {
+                    df
+                } else {
+                    (l0 + (l1 - l0) * (t - t0) / (t1 - t0)).exp()
+                };
+            }
+            (t0, l0) = (t1, l1);
+        }
+        if t0 > 0.0 { (l0 * t / t0).exp() } else { 1.0 }
+    }
+
+    /**
+    Continuously compounded zero rate at a date. The rate at the reference date is
+    taken as that of the first pillar.
+
+    - dt = date
+     */
+    pub fn zero_rate(&self, dt: NDt) -> f64 {
+        self.zero_rate_time(self.time(dt))
+    }
+
+    /**
+    Continuously compounded zero rate at a time from the reference date
+
+    - t = time in years as per the basis
+     */
+    pub fn zero_rate_time(&self, t: f64) -> f64 {
+        let t = if t > 1e-9 {
+            t
+        } else {
+            self.pillars.first().map_or(1.0, |&(d, _)| self.time(d))
+        };
+        -self.df_time(t).ln() / t
+    }
+
+    /**
+    Continuously compounded forward rate between two dates
+
+    - dt0 = start date of the forward period
+    - dt1 = end date of the forward period
+     */
+    pub fn forward_rate(&self, dt0: NDt, dt1: NDt) -> f64 {
+        (self.df(dt0) / self.df(dt1)).ln() / crate::yearfrac(dt0, dt1, self.basis)
+    }
+
+    /**
+    Simple forward rate between two dates with accrual as per the basis
+
+    - dt0 = start date of the forward period
+    - dt1 = end date of the forward period
+     */
+    pub fn simple_forward_rate(&self, dt0: NDt, dt1: NDt) -> f64 {
+        (self.df(dt0) / self.df(dt1) - 1.0) / crate::yearfrac(dt0, dt1, self.basis)
+    }
+
+    /**
+    Sample the curve as Nominal spot rates at a regular grid i / freq years from the
+    reference date, so that the curve may be used by the functions taking a RateCurve.
+
+    - freq  = frequency of the RateCurve
+    - t_max = time in years till which the RateCurve is needed
+     */
+    pub fn to_ratecurve(&self, freq: f64, t_max: f64) -> RateCurve {
+        RateCurve::from_zero_fn(freq, t_max, |t| self.zero_rate_time(t))
+    }
+
+    /**
+    Bootstrap the curve from market quotes such that every quote is repriced exactly.
+    The discount factor of each pillar is solved in ascending order of the maturity
+    of the instruments. Each pillar is set by one instrument, so the instruments must
+    have distinct maturities after the reference date.
+
+    - dt_ref        = Reference date of the curve
+    - basis         = Day count convention of the curve and the instruments
+    - instruments   = Market quotes
+     */
+    pub fn bootstrap(
+        dt_ref: NDt,
+        basis: DayCountConvention,
+        instruments: &[CurveInstrument],
+    ) -> Result<DiscountCurve, BootstrapError> {
+        let mut ins: Vec<&CurveInstrument> = instruments.iter().collect();
+        ins.sort_by_key(|x| x.maturity());
+        if let Some(w) = ins.windows(2).find(|w| w[0].maturity() == w[1].maturity()) {
+            return Err(BootstrapError::DuplicateMaturity(w[0].maturity()));
+        }
+        if let Some(x) = ins.first().filter(|x| x.maturity() <= dt_ref) {
+            return Err(BootstrapError::MaturityNotAfterReference(x.maturity()));
+        }
+        let mut dc = DiscountCurve {
+            dt_ref,
+            basis,
+            pillars: vec![],
+        };
+        for x in ins {
+            let t = dc.time(x.maturity());
+            dc.pillars.push((x.maturity(), 1.0));
+            let n = dc.pillars.len() - 1;
+            let z = crate::newt_raph(
+                |z| {
+                    let mut dx = dc.clone();
+                    dx.pillars[n].1 = (-z * t).exp();
+                    x.value(&dx)
+                },
+                0.03,
+                1e-12,
+            )
+            .ok_or(BootstrapError::NoSolution(x.maturity()))?;
+            dc.pillars[n].1 = (-z * t).exp();
+        }
+        Ok(dc)
+    }
+}
+
+/**
+Error in bootstrapping a DiscountCurve, with the maturity of the instrument at fault
+
+- DuplicateMaturity         => Two instruments mature on the same date
+- MaturityNotAfterReference => Instrument maturing on or before the reference date
+- NoSolution                => No discount factor reprices the instrument
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapError {
+    DuplicateMaturity(NDt),
+    MaturityNotAfterReference(NDt),
+    NoSolution(NDt),
+}
+
+impl std::fmt::Display for BootstrapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::DuplicateMaturity(d) => write!(f, "more than one instrument maturing on {d}"),
+            Self::MaturityNotAfterReference(d) => {
+                write!(
+                    f,
+                    "instrument maturing on {d} is not after the reference date"
+                )
+            }
+            Self::NoSolution(d) => write!(f, "no discount factor reprices the quote at {d}"),
+        }
+    }
+}
+
+impl std::error::Error for BootstrapError {}
+
+/**
+Convexity adjustment of the futures rate to get the forward rate as per the Ho-Lee model
+= 0.5 * sigma^2 * t1 * t2
+
+- sigma = volatility of the short rate
+- t1    = time to the start of the futures period
+- t2    = time to the end of the futures period
+ */
+pub fn futures_convexity(sigma: f64, t1: f64, t2: f64) -> f64 {
+    0.5 * sigma * sigma * t1 * t2
+}
+
+/**
+Enum defining the market quotes used to bootstrap a DiscountCurve. All rates are simple
+rates with accrual as per the basis of the curve.
+
+- Deposit   => Deposit from the reference date to dt_end at rate
+- FRA       => FRA from dt_start to dt_end at rate
+- Future    => Interest rate futures on the period dt_start to dt_end, quoted as price = 100 - rate in percent, with sigma being the short rate volatility for the convexity adjustment
+- Swap      => Par swap from the reference date to dt_end with fixed rate paid freq times a year against the floating leg
+- Bond      => Coupon bond with the dirty price at the reference date
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CurveInstrument {
+    Deposit {
+        dt_end: NDt,
+        rate: f64,
+    },
+    FRA {
+        dt_start: NDt,
+        dt_end: NDt,
+        rate: f64,
+    },
+    Future {
+        dt_start: NDt,
+        dt_end: NDt,
+        price: f64,
+        sigma: f64,
+    },
+    Swap {
+        dt_end: NDt,
+        rate: f64,
+        freq: f64,
+    },
+    Bond {
+        bond: XCouponBond,
+        price: f64,
+    },
+}
+
+impl CurveInstrument {
+    /** Maturity date of the instrument, which is the pillar it adds to the curve */
+    pub fn maturity(&self) -> NDt {
+        match self {
+            Self::Deposit { dt_end, .. }
+            | Self::FRA { dt_end, .. }
+            | Self::Future { dt_end, .. }
+            | Self::Swap { dt_end, .. } => *dt_end,
+            Self::Bond { bond, .. } => bond.dt_maturity,
+        }
+    }
+
+    /**
+    Value of the instrument on a DiscountCurve per unit notional, which is zero when
+    the curve reprices the quote.
+
+    - dc = DiscountCurve
+     */
+    pub fn value(&self, dc: &DiscountCurve) -> f64 {
+        let tau = |d0, d1| crate::yearfrac(d0, d1, dc.basis);
+        let fwd = |d0, d1, r: f64| dc.df(d0) / (1.0 + r * tau(d0, d1)) - dc.df(d1);
+        match *self {
+            Self::Deposit { dt_end, rate } => fwd(dc.dt_ref, dt_end, rate),
+            Self::FRA {
+                dt_start,
+                dt_end,
+                rate,
+            } => fwd(dt_start, dt_end, rate),
+            Self::Future {
+                dt_start,
+                dt_end,
+                price,
+                sigma,
+            } => {
+                let cx = futures_convexity(sigma, dc.time(dt_start), dc.time(dt_end));
+                fwd(dt_start, dt_end, (100.0 - price) / 100.0 - cx)
+            }
+            Self::Swap { dt_end, rate, freq } => {
+                let m = (12.0 / freq).round() as u32;
+                let mut d1 = dt_end;
+                let mut annuity = 0.0;
+                for k in 1.. {
+                    let d0 = (dt_end - Months::new(m * k)).max(dc.dt_ref);
+                    annuity += tau(d0, d1) * dc.df(d1);
+                    if d0 <= dc.dt_ref {
+                        break;
+                    }
+                    d1 = d0;
+                }
+                1.0 - dc.df(dt_end) - rate * annuity
+            }
+            Self::Bond { bond, price } => (bond.price_curve(dc) - price) / bond.par,
+        }
+    }
+}
+
+impl CouponBond {
+    /**
+    Price of the Coupon bond on a DiscountCurve, with the coupons due at i / freq years
+    from the reference date
+
+    - dc = DiscountCurve
+     */
+    pub fn price_discountcurve(&self, dc: &DiscountCurve) -> f64 {
+        self.cashflow_schedule()
+            .iter()
+            .map(|&(t, c)| c * dc.df_time(t))
+            .sum()
+    }
+}
+
+impl XCouponBond {
+    /**
+    Dirty price of the bond at the reference date of a DiscountCurve
+
+    - dc = DiscountCurve
+     */
+    pub fn price_curve(&self, dc: &DiscountCurve) -> f64 {
+        let cpn = self.par * self.c / self.freq;
+        let (_, dts) = self.coupon_dates(dc.dt_ref);
+        dts.iter().map(|&d| cpn * dc.df(d)).sum::<f64>() + self.par * dc.df(self.dt_maturity)
+    }
+}
+
+#[cfg(test)]
+mod curves_fn {
+    use super::*;
+    use crate::DayCountConvention::*;
+    use crate::approx;
+
+    #[test]
+    fn bootstrap_curves() {
+        let dt = |y, m, d| NDt::from_ymd_opt(y, m, d).unwrap();
+        let d0 = dt(2024, 1, 15);
+        let quotes = vec![
+            CurveInstrument::Deposit {
+                dt_end: dt(2024, 2, 15),
+                rate: 0.0530,
+            },
+            CurveInstrument::Deposit {
+                dt_end: dt(2024, 4, 15),
+                rate: 0.0532,
+            },
+            CurveInstrument::FRA {
+                dt_start: dt(2024, 4, 15),
+                dt_end: dt(2024, 7, 15),
+                rate: 0.0520,
+            },
+            CurveInstrument::Future {
+                dt_start: dt(2024, 7, 15),
+                dt_end: dt(2024, 10, 15),
+                price: 95.00,
+                sigma: 0.012,
+            },
+            CurveInstrument::Swap {
+                dt_end: dt(2026, 1, 15),
+                rate: 0.0460,
+                freq: 2.0,
+            },
+            CurveInstrument::Bond {
+                bond: XCouponBond {
+                    par: 100.0,
+                    c: 0.04,
+                    freq: 2.0,
+                    dt_maturity: dt(2029, 1, 15),
+                },
+                price: 98.0,
+            },
+            CurveInstrument::Swap {
+                dt_end: dt(2034, 1, 15),
+                rate: 0.0420,
+                freq: 1.0,
+            },
+        ];
+        let dc = DiscountCurve::bootstrap(d0, ACT360, &quotes).unwrap();
+        assert_eq!(dc.pillars.len(), quotes.len());
+        quotes
+            .iter()
+            .for_each(|q| assert!(q.value(&dc).abs() < 1e-10));
+        assert!(approx(
+            dc.df(dt(2024, 2, 15)),
+            1.0 / (1.0 + 0.053 * 31.0 / 360.0)
+        ));
+        assert_eq!(dc.df(d0), 1.0);
+        assert!(dc.df(dt(2040, 1, 15)) < dc.df(dt(2034, 1, 15)));
+        assert!(approx(futures_convexity(0.012, 0.5, 0.75), 0.000027));
+
+        let dup = [
+            CurveInstrument::Deposit {
+                dt_end: dt(2024, 2, 15),
+                rate: 0.0530,
+            },
+            CurveInstrument::Deposit {
+                dt_end: dt(2024, 2, 15),
+                rate: 0.0531,
+            },
+        ];
+        assert_eq!(
+            DiscountCurve::bootstrap(d0, ACT360, &dup).unwrap_err(),
+            BootstrapError::DuplicateMaturity(dt(2024, 2, 15))
+        );
+        assert_eq!(
+            DiscountCurve::bootstrap(d0, ACT360, &dup[..1])
+                .unwrap()
+                .pillars
+                .len(),
+            1
+        );
+        let expired = [CurveInstrument::Deposit {
+            dt_end: d0,
+            rate: 0.0530,
+        }];
+        assert_eq!(
+            DiscountCurve::bootstrap(d0, ACT360, &expired).unwrap_err(),
+            BootstrapError::MaturityNotAfterReference(d0)
+        );
+    }
+
+    #[test]
+    fn date_curves() {
+        let dt = |y, m, d| NDt::from_ymd_opt(y, m, d).unwrap();
+        let d0 = dt(2024, 1, 15);
+        assert_eq!(tenor_date(d0, "1W"), Some(dt(2024, 1, 22)));
+        assert_eq!(tenor_date(d0, "18m"), Some(dt(2025, 7, 15)));
+        assert_eq!(tenor_date(d0, "Y"), None);
+
+        let dc = DiscountCurve::from_tenors(
+            d0,
+            ACT365,
+            &[
+                ("1W", 0.0530),
+                ("1M", 0.0532),
+                ("3M", 0.0535),
+                ("6M", 0.0525),
+                ("1Y", 0.0500),
+                ("2Y", 0.0460),
+                ("5Y", 0.0420),
+                ("10Y", 0.0415),
+                ("30Y", 0.0430),
+            ],
+        )
+        .unwrap();
+        assert!(DiscountCurve::from_tenors(d0, ACT365, &[("1M", 0.05), ("1X", 0.05)]).is_none());
+        assert!(approx(dc.zero_rate(dt(2029, 1, 15)), 0.0420));
+        assert!(approx(dc.zero_rate(d0), 0.0530));
+        assert!(approx(
+            dc.df(dt(2024, 7, 15)),
+            (-0.0525 * 182.0 / 365.0f64).exp()
+        ));
+        let (d1, d2) = (dt(2025, 1, 15), dt(2026, 1, 15));
+        let tau = dc.time(d2) - dc.time(d1);
+        assert!(approx(
+            dc.df(d1) * (-dc.forward_rate(d1, d2) * tau).exp(),
+            dc.df(d2)
+        ));
+        assert!(approx(
+            dc.df(d1) / (1.0 + dc.simple_forward_rate(d1, d2) * tau),
+            dc.df(d2)
+        ));
+
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 7.0,
+        };
+        assert!(approx(
+            cb.price_ratecurve(&dc.to_ratecurve(2.0, 7.0)),
+            cb.price_discountcurve(&dc)
+        ));
+    }
+}
@@ -11,6 +11,31 @@ The module describes the formulas of bond durations.
 You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
+/**
+Effective duration of a price function w.r.t. a rate or spread
+= (P(y - dy) - P(y + dy)) / (2 * P(y) * dy)
+
+- price = price as a function of the rate or spread
+- y     = rate or spread at which the duration is sought
+- dy    = shock to the rate or spread
+ */
+pub fn effective_duration(price: impl Fn(f64) -> f64, y: f64, dy: f64) -> f64 {
+    (price(y - dy) - price(y + dy)) / (2.0 * price(y) * dy)
+}
+
+/**
+Effective convexity of a price function w.r.t. a rate or spread
+= (P(y - dy) + P(y + dy) - 2 * P(y)) / (P(y) * dy^2)
+
+- price = price as a function of the rate or spread
+- y     = rate or spread at which the convexity is sought
+- dy    = shock to the rate or spread
+ */
+pub fn effective_convexity(price: impl Fn(f64) -> f64, y: f64, dy: f64) -> f64 {
+    let p0 = price(y);
+    (price(y - dy) + price(y + dy) - 2.0 * p0) / (p0 * dy * dy)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -18,4 +43,23 @@ mod tests {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn effective_measures() {
+        use super::*;
+        let cb = crate::fixedincomes::bonds::CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        assert!(crate::approx(
+            effective_duration(|r| cb.price(r), 0.05, 1e-4),
+            4.376032181938427
+        ));
+        assert!(crate::approx(
+            effective_convexity(|r| cb.price(r), 0.05, 1e-4),
+            22.612322879922427
+        ));
+    }
 }
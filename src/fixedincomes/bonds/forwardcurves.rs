@@ -0,0 +1,292 @@
+/*!
+Implement Forward curve modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::forwardcurves <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the forward curve derived from a spot curve, giving the
+instantaneous forward rates, the forward rates for any start and tenor under any
+compounding, and the discrete forward curves for a reset frequency which are used to
+project the coupons of Floating rate notes.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::FloatingRateNotes;
+use super::rates::{RateCurve, Rates};
+use crate::DayCountConvention;
+use chrono::naive::NaiveDate as NDt;
+use serde::{Deserialize, Serialize};
+
+/**
+Enum defining the compounding of a rate over a tenor
+
+- Simple        => Simple interest, growth = 1 + r t
+- Nominal       => Nominal rate compounded freq times a period, growth = (1 + r / freq)^(freq t)
+- Effective     => Effective rate compounded once a period, growth = (1 + r)^t
+- Exponential   => Continuously compounded rate, growth = exp(r t)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Compounding {
+    Simple,
+    Nominal(f64),
+    Effective,
+    Exponential,
+}
+
+impl Compounding {
+    /**
+    Growth of 1 unit over a tenor at a rate
+
+    - rate  = rate with the compounding
+    - tenor = tenor given as period
+     */
+    pub fn growth(&self, rate: f64, tenor: f64) -> f64 {
+        match self {
+            Self::Simple => 1.0 + rate * tenor,
+            Self::Nominal(freq) => (1.0 + rate / freq).powf(freq * tenor),
+            Self::Effective => (1.0 + rate).powf(tenor),
+            Self::Exponential => (rate * tenor).exp(),
+        }
+    }
+
+    /**
+    Rate with the compounding which gives a growth over a tenor
+
+    - growth    = growth of 1 unit over the tenor
+    - tenor     = tenor given as period
+     */
+    pub fn rate(&self, growth: f64, tenor: f64) -> f64 {
+        match self {
+            Self::Simple => (growth - 1.0) / tenor,
+            Self::Nominal(freq) => (growth.powf(1.0 / (freq * tenor)) - 1.0) * freq,
+            Self::Effective => growth.powf(1.0 / tenor) - 1.0,
+            Self::Exponential => growth.ln() / tenor,
+        }
+    }
+}
+
+/**
+ForwardCurve : struct defining the forward curve implied by a spot RateCurve
+
+- spot  = Spot RateCurve of any type of rates
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardCurve {
+    pub spot: RateCurve,
+}
+
+impl ForwardCurve {
+    /**
+    Forward curve from Rates, converted to spot rates if needed
+
+    - rates = Rates
+     */
+    pub fn new(rates: &Rates) -> Self {
+        ForwardCurve {
+            spot: rates.to_spot().curve().clone(),
+        }
+    }
+
+    /**
+    Discount factor at a time
+
+    - t = time given as period
+     */
+    pub fn df(&self, t: f64) -> f64 {
+        self.spot.pv(1.0, t)
+    }
+
+    /**
+    Instantaneous forward rate at a time as a continuously compounded rate, by central
+    difference of the log discount factors
+
+    - t = time given as period
+     */
+    pub fn instantaneous(&self, t: f64) -> f64 {
+        let h = 1e-5;
+        let t0 = (t - h).max(0.0);
+        (self.df(t0) / self.df(t + h)).ln() / (t + h - t0)
+    }
+
+    /**
+    Forward rate for a period starting at a time for a tenor
+
+    - start         = start of the forward period
+    - tenor         = tenor of the forward period
+    - compounding   = compounding of the forward rate
+     */
+    pub fn forward(&self, start: f64, tenor: f64, compounding: Compounding) -> f64 {
+        compounding.rate(self.df(start) / self.df(start + tenor), tenor)
+    }
+
+    /**
+    Forward rate for a period between two dates, with the times measured from the
+    reference date of the curve
+
+    - dt_ref        = Reference date of the spot curve
+    - dt_start      = start date of the forward period
+    - dt_end        = end date of the forward period
+    - basis         = Day count convention
+    - compounding   = compounding of the forward rate
+     */
+    pub fn forward_dates(
+        &self,
+        dt_ref: NDt,
+        dt_start: NDt,
+        dt_end: NDt,
+        basis: DayCountConvention,
+        compounding: Compounding,
+    ) -> f64 {
+        let t0 = crate::yearfrac(dt_ref, dt_start, basis);
+        let t1 = crate::yearfrac(dt_ref, dt_end, basis);
+        compounding.rate(self.df(t0) / self.df(t1), t1 - t0)
+    }
+
+    /**
+    Discrete forward rates for each reset period (i / reset_freq, (i + 1) / reset_freq)
+
+    - reset_freq    = Number of resets per period
+    - t_max         = time till which the forward rates are needed
+    - compounding   = compounding of the forward rates
+     */
+    pub fn discrete(&self, reset_freq: f64, t_max: f64, compounding: Compounding) -> Vec<f64> {
+        let tenor = 1.0 / reset_freq;
+        (0..(t_max * reset_freq).round() as usize)
+            .map(|i| self.forward(i as f64 * tenor, tenor, compounding))
+            .collect()
+    }
+
+    /**
+    Discrete forward curve for a reset frequency as Rates::ForwardRates of Nominal rates
+
+    - reset_freq    = Number of resets per period
+    - t_max         = time till which the forward curve is needed
+     */
+    pub fn to_forward_rates(&self, reset_freq: f64, t_max: f64) -> Rates {
+        Rates::ForwardRates {
+            rate: RateCurve::NominalRateCurve {
+                rate: self.discrete(reset_freq, t_max, Compounding::Nominal(reset_freq)),
+                freq: reset_freq,
+            },
+        }
+    }
+}
+
+impl FloatingRateNotes {
+    /**
+    Coupons projected for each reset period from a ForwardCurve, after the margin, cap
+    and floor
+
+    - fc = ForwardCurve of the index
+     */
+    pub fn projected_coupons(&self, fc: &ForwardCurve) -> Vec<f64> {
+        self.projected_index(&fc.spot)
+            .iter()
+            .map(|&r| self.coupon_rate(r) * self.par / self.freq)
+            .collect()
+    }
+
+    /**
+    Price of Floating Rate Note with the coupons projected from a ForwardCurve and
+    discounted at the forward rates plus the discount margin
+
+    - fc                = ForwardCurve of the index
+    - discount_margin   = Discount margin
+     */
+    pub fn price_forwardcurve(&self, fc: &ForwardCurve, discount_margin: f64) -> f64 {
+        self.price_ratecurve(&fc.spot, discount_margin)
+    }
+}
+
+#[cfg(test)]
+mod forwardcurves_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn forward_curves() {
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+        let sr = Rates::SpotRates { rate: rc.clone() };
+        let fc = ForwardCurve::new(&sr);
+        assert!(approx(
+            fc.forward(1.0, 0.5, Compounding::Nominal(2.0)),
+            sr.forward_rate(1.0, 0.5)
+        ));
+        assert!(approx(
+            ForwardCurve::new(&sr.to_par()).forward(1.5, 2.0, Compounding::Nominal(2.0)),
+            sr.forward_rate(1.5, 2.0)
+        ));
+        let g = fc.df(1.0) / fc.df(3.0);
+        [
+            Compounding::Simple,
+            Compounding::Nominal(4.0),
+            Compounding::Effective,
+            Compounding::Exponential,
+        ]
+        .iter()
+        .for_each(|c| assert!(approx(c.growth(fc.forward(1.0, 2.0, *c), 2.0), g)));
+
+        let (t, h) = (2.25, 1e-3);
+        let fwd = fc.forward(t - h / 2.0, h, Compounding::Exponential);
+        assert!((fc.instantaneous(t) - fwd).abs() < 1e-6);
+        assert!(approx(
+            (0..8)
+                .map(|i| fc.forward(i as f64 * 0.5, 0.5, Compounding::Exponential) * 0.5)
+                .sum::<f64>(),
+            -fc.df(4.0).ln()
+        ));
+
+        let d0 = NDt::from_ymd_opt(2024, 1, 15).unwrap();
+        assert!(approx(
+            fc.forward_dates(
+                d0,
+                NDt::from_ymd_opt(2025, 1, 15).unwrap(),
+                NDt::from_ymd_opt(2025, 7, 15).unwrap(),
+                DayCountConvention::US30360,
+                Compounding::Simple
+            ),
+            fc.forward(1.0, 0.5, Compounding::Simple)
+        ));
+
+        let Rates::ForwardRates { rate: fr } = fc.to_forward_rates(2.0, 5.0) else {
+            panic!()
+        };
+        assert!(approx(fr.rate_estim(2.0), sr.forward_rate(1.5, 0.5)));
+        assert!(approx(
+            Rates::ForwardRates { rate: fr }.to_spot().rate_estim(5.0),
+            0.0330
+        ));
+
+        let frn = FloatingRateNotes {
+            cap: Some(0.035),
+            ..FloatingRateNotes::new(100.0, 0.005, 4.0, 5.0)
+        };
+        let ix = frn.projected_index(&rc);
+        assert_eq!(ix.len(), 20);
+        assert!(approx(
+            ix[5],
+            fc.forward(1.25, 0.25, Compounding::Nominal(4.0))
+        ));
+        assert!(approx(
+            frn.price_forwardcurve(&fc, 0.002),
+            frn.price_ratecurve(&rc, 0.002)
+        ));
+        assert!(approx(frn.projected_coupons(&fc)[19], 0.035 * 100.0 / 4.0));
+        let par = FloatingRateNotes {
+            quoted_margin: 0.0,
+            cap: None,
+            ..frn
+        };
+        assert!(approx(par.price_forwardcurve(&fc, 0.0), 100.0));
+        assert!(frn.price_forwardcurve(&fc, 0.0) < 100.0 + 100.0 * 0.005 * 5.0);
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+Implement Bond Horizon analysis modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::horizons <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the decomposition of the expected return of a Bond over a horizon
+into coupon income, reinvestment income, rolldown and price change under a scenario.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::CouponBond;
+use super::rates::RateCurve;
+
+/**
+Components of the return of a Bond over a horizon
+
+- price_begin       = Price of the bond today
+- price_end         = Price of the bond at the horizon under the scenario
+- coupon_income     = Coupons received till the horizon
+- reinvestment      = Interest earned on the coupons reinvested till the horizon
+- rolldown          = Price change from ageing of the bond along the unchanged RateCurve
+- price_change      = Price change at the horizon from the scenario RateCurve
+- total_return      = Total return over the horizon as a fraction of price_begin
+- annualized_return = Total return given as an effective rate per period
+- annualized_nominal = Total return given as a nominal rate compounded at coupon frequency
+ */
+#[derive(Debug, Copy, Clone)]
+pub struct HorizonReturn {
+    pub price_begin: f64,
+    pub price_end: f64,
+    pub coupon_income: f64,
+    pub reinvestment: f64,
+    pub rolldown: f64,
+    pub price_change: f64,
+    pub total_return: f64,
+    pub annualized_return: f64,
+    pub annualized_nominal: f64,
+}
+
+impl CouponBond {
+    /**
+    Price of the bond aged by n coupon periods along a RateCurve. The par is taken
+    as received if the bond has matured.
+     */
+    fn aged_price(&self, n: usize, rc: &RateCurve) -> f64 {
+        let t_life = self.t_life - n as f64 / self.freq;
+        if t_life * self.freq < 0.5 {
+            self.par
+        } else {
+            CouponBond { t_life, ..*self }.price_ratecurve(rc)
+        }
+    }
+
+    /**
+    Horizon analysis of the bond. The horizon is rounded to the nearest coupon date.
+
+    - rc        = Spot RateCurve today
+    - horizon   = Horizon given in periods
+    - reinvest  = Reinvestment rate of coupons as Nominal rate compounded at coupon frequency
+    - scenario  = Spot RateCurve at the horizon under the scenario
+
+    Returns None if the horizon rounds to less than one coupon period, as the return
+    cannot be annualized
+     */
+    pub fn horizon_return(
+        &self,
+        rc: &RateCurve,
+        horizon: f64,
+        reinvest: f64,
+        scenario: &RateCurve,
+    ) -> Option<HorizonReturn> {
+        let n = ((horizon * self.freq).round() as usize).min((self.t_life * self.freq) as usize);
+        if n == 0 {
+            return None;
+        }
+        let h = n as f64 / self.freq;
+        let cpn = self.par * self.c / self.freq;
+
+        let price_begin = self.price_ratecurve(rc);
+        let price_roll = self.aged_price(n, rc);
+        let price_end = self.aged_price(n, scenario);
+
+        let coupon_income = cpn * n as f64;
+        let reinvestment = (1..=n)
+            .map(|i| crate::fvm(reinvest, h - i as f64 / self.freq, self.freq, cpn))
+            .sum::<f64>()
+            - coupon_income;
+
+        let total_return = (coupon_income + reinvestment + price_end) / price_begin - 1.0;
+        let annualized_return = (1.0 + total_return).powf(1.0 / h) - 1.0;
+
+        Some(HorizonReturn {
+            price_begin,
+            price_end,
+            coupon_income,
+            reinvestment,
+            rolldown: price_roll - price_begin,
+            price_change: price_end - price_roll,
+            total_return,
+            annualized_return,
+            annualized_nominal: crate::eff_nom_rate(annualized_return, self.freq),
+        })
+    }
+}
+
+#[cfg(test)]
+mod horizons_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn horizon_returns() {
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let flat = RateCurve::NominalRateCurve {
+            rate: vec![0.04; 10],
+            freq: 2.0,
+        };
+        let hr = cb.horizon_return(&flat, 2.0, 0.04, &flat).unwrap();
+        assert!(approx(hr.annualized_nominal, 0.04));
+        assert!(approx(hr.coupon_income, 10.0));
+        assert!(approx(
+            hr.price_begin * (1.0 + hr.total_return),
+            hr.price_begin + hr.coupon_income + hr.reinvestment + hr.rolldown + hr.price_change
+        ));
+        assert!(approx(hr.price_change, 0.0));
+        assert!(cb.horizon_return(&flat, 0.0, 0.04, &flat).is_none());
+        assert!(cb.horizon_return(&flat, 0.2, 0.04, &flat).is_none());
+
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+        let hr = cb.horizon_return(&rc, 1.0, 0.03, &rc.shift(0.01)).unwrap();
+        assert!(hr.rolldown < 0.0 && hr.price_change < 0.0);
+        assert!(approx(hr.reinvestment, 2.5 * 0.015));
+
+        let hr = cb.horizon_return(&rc, 5.0, 0.03, &rc.shift(0.01)).unwrap();
+        assert!(approx(hr.price_end, 100.0));
+        assert!(approx(hr.price_change, 0.0));
+    }
+}
@@ -0,0 +1,548 @@
+/*!
+Implement Interpolation modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::interpolations <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the interpolation of yield curves between the pillars along with
+the extrapolation beyond them. The interpolation methods are pluggable through the
+Interpolation trait and work on continuously compounded zero rates. A RateCurve takes
+any of them through RateCurve::interpolated, after which all the functions using the
+RateCurve price with the chosen interpolation.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use serde::{Deserialize, Serialize};
+
+/**
+Trait for interpolation of continuously compounded zero rates between the pillars. The
+coefficients are fitted once to the pillars and then used for each interpolation.
+
+- t     = Vector of pillar times in ascending order, with at least 2 pillars
+- z     = Vector of zero rates at the pillars
+- coef  = Coefficients returned by fit for the same pillars
+- x     = Time between t[0] and t[n - 1] at which the zero rate is sought
+ */
+pub trait Interpolation {
+    fn fit(&self, _t: &[f64], _z: &[f64]) -> Vec<f64> {
+        vec![]
+    }
+    fn interpolate(&self, t: &[f64], z: &[f64], coef: &[f64], x: f64) -> f64;
+}
+
+/**
+Enum defining the extrapolation of zero rates beyond the pillars
+
+- Flat      => Zero rate of the nearest pillar
+- Linear    => Zero rate extended along the slope of the nearest segment
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Extrapolation {
+    Flat,
+    Linear,
+}
+
+/** Index i of the segment (t[i - 1], t[i]] containing x */
+fn segment(t: &[f64], x: f64) -> usize {
+    t.iter()
+        .skip(1)
+        .take_while(|&&ti| ti < x)
+        .count()
+        .min(t.len() - 2)
+        + 1
+}
+
+/** Linear interpolation on zero rates */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LinearZero;
+
+impl Interpolation for LinearZero {
+    fn interpolate(&self, t: &[f64], z: &[f64], _coef: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        z[i - 1] + (z[i] - z[i - 1]) * (x - t[i - 1]) / (t[i] - t[i - 1])
+    }
+}
+
+/** Log-linear interpolation on discount factors, i.e. flat continuous forward rates */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LogLinearDiscount;
+
+impl Interpolation for LogLinearDiscount {
+    fn interpolate(&self, t: &[f64], z: &[f64], _coef: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        let (r0, r1) = (z[i - 1] * t[i - 1], z[i] * t[i]);
+        (r0 + (r1 - r0) * (x - t[i - 1]) / (t[i] - t[i - 1])) / x
+    }
+}
+
+/** Flat simple forward rates between the pillars, i.e. linear interpolation on 1 / discount factor */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct FlatForward;
+
+impl Interpolation for FlatForward {
+    fn interpolate(&self, t: &[f64], z: &[f64], _coef: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        let (c0, c1) = ((z[i - 1] * t[i - 1]).exp(), (z[i] * t[i]).exp());
+        (c0 + (c1 - c0) * (x - t[i - 1]) / (t[i] - t[i - 1])).ln() / x
+    }
+}
+
+/** Natural cubic spline on zero rates */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct NaturalCubic;
+
+impl Interpolation for NaturalCubic {
+    /** Second derivatives at the pillars */
+    fn fit(&self, t: &[f64], z: &[f64]) -> Vec<f64> {
+        let n = t.len();
+        let h: Vec<f64> = t.windows(2).map(|w| w[1] - w[0]).collect();
+
+        // Second derivatives by the Thomas algorithm with m[0] = m[n - 1] = 0
+        let mut m = vec![0.0; n];
+        let (mut c, mut d) = (vec![0.0; n], vec![0.0; n]);
+        for i in 1..n - 1 {
+            let a = h[i - 1];
+            let b = 2.0 * (h[i - 1] + h[i]) - a * c[i - 1];
+            let r = 6.0 * ((z[i + 1] - z[i]) / h[i] - (z[i] - z[i - 1]) / h[i - 1]);
+            c[i] = h[i] / b;
+            d[i] = (r - a * d[i - 1]) / b;
+        }
+        for i in (1..n - 1).rev() {
+            m[i] = d[i] - c[i] * m[i + 1];
+        }
+        m
+    }
+
+    fn interpolate(&self, t: &[f64], z: &[f64], m: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        let (a, b) = (t[i] - x, x - t[i - 1]);
+        let hi = t[i] - t[i - 1];
+        (m[i - 1] * a.powi(3) + m[i] * b.powi(3)) / (6.0 * hi)
+            + (z[i - 1] / hi - m[i - 1] * hi / 6.0) * a
+            + (z[i] / hi - m[i] * hi / 6.0) * b
+    }
+}
+
+/** Cubic Hermite interpolation on zero rates with Bessel slopes */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Hermite;
+
+impl Interpolation for Hermite {
+    /** Slopes at the pillars */
+    fn fit(&self, t: &[f64], z: &[f64]) -> Vec<f64> {
+        let n = t.len();
+        let d: Vec<f64> = (1..n)
+            .map(|i| (z[i] - z[i - 1]) / (t[i] - t[i - 1]))
+            .collect();
+        (0..n)
+            .map(|k| {
+                if k == 0 {
+                    d[0]
+                } else if k == n - 1 {
+                    d[n - 2]
+                } else {
+                    let (h0, h1) = (t[k] - t[k - 1], t[k + 1] - t[k]);
+                    (h1 * d[k - 1] + h0 * d[k]) / (h0 + h1)
+                }
+            })
+            .collect()
+    }
+
+    fn interpolate(&self, t: &[f64], z: &[f64], slope: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        let h = t[i] - t[i - 1];
+        let s = (x - t[i - 1]) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        (2.0 * s3 - 3.0 * s2 + 1.0) * z[i - 1]
+            + (s3 - 2.0 * s2 + s) * h * slope[i - 1]
+            + (-2.0 * s3 + 3.0 * s2) * z[i]
+            + (s3 - s2) * h * slope[i]
+    }
+}
+
+/**
+Monotone convex interpolation of Hagan and West on the forward rates, which keeps the
+forward curve continuous and preserves the monotonicity of the discrete forwards.
+ */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MonotoneConvex;
+
+impl MonotoneConvex {
+    /** Integral of g over (0, x) for the boundary values g0, g1 of a segment */
+    fn g_integral(g0: f64, g1: f64, x: f64) -> f64 {
+        if g0 == 0.0 && g1 == 0.0 {
+            0.0
+        } else if (g0 < 0.0 && -0.5 * g0 <= g1 && g1 <= -2.0 * g0)
+            || (g0 > 0.0 && -0.5 * g0 >= g1 && g1 >= -2.0 * g0)
+        {
+            g0 * (x - 2.0 * x * x + x.powi(3)) + g1 * (-x * x + x.powi(3))
+        } else if (g0 < 0.0 && g1 > -2.0 * g0) || (g0 > 0.0 && g1 < -2.0 * g0) {
+            let eta = (g1 + 2.0 * g0) / (g1 - g0);
+            if x <= eta {
+                g0 * x
+            } else {
+                g0 * x + (g1 - g0) * (x - eta).powi(3) / (1.0 - eta).powi(2) / 3.0
+            }
+        } else if (g0 > 0.0 && 0.0 > g1 && g1 > -0.5 * g0)
+            || (g0 < 0.0 && 0.0 < g1 && g1 < -0.5 * g0)
+        {
+            let eta = 3.0 * g1 / (g1 - g0);
+            if x < eta {
+                g1 * x + (g0 - g1) / 3.0 * (eta - (eta - x).powi(3) / (eta * eta))
+            } else {
+                g1 * x + (g0 - g1) * eta / 3.0
+            }
+        } else {
+            let eta = g1 / (g1 + g0);
+            let a = -g0 * g1 / (g0 + g1);
+            if x <= eta {
+                a * x + (g0 - a) / 3.0 * (eta - (eta - x).powi(3) / (eta * eta))
+            } else {
+                a * x
+                    + (g0 - a) * eta / 3.0
+                    + (g1 - a) / 3.0 * (x - eta).powi(3) / (1.0 - eta).powi(2)
+            }
+        }
+    }
+}
+
+impl Interpolation for MonotoneConvex {
+    /** Instantaneous forward rates at the time 0 and at the pillars */
+    fn fit(&self, t: &[f64], z: &[f64]) -> Vec<f64> {
+        let mut tx = vec![0.0];
+        let mut rt = vec![0.0];
+        tx.extend_from_slice(t);
+        rt.extend(t.iter().zip(z).map(|(t, z)| t * z));
+        let n = tx.len() - 1;
+
+        let fd: Vec<f64> = (1..=n)
+            .map(|i| (rt[i] - rt[i - 1]) / (tx[i] - tx[i - 1]))
+            .collect();
+        let mut f = vec![0.0; n + 1];
+        for i in 1..n {
+            let w = tx[i + 1] - tx[i - 1];
+            f[i] = (tx[i] - tx[i - 1]) / w * fd[i] + (tx[i + 1] - tx[i]) / w * fd[i - 1];
+        }
+        f[0] = fd[0] - 0.5 * (f[1] - fd[0]);
+        f[n] = fd[n - 1] - 0.5 * (f[n - 1] - fd[n - 1]);
+        if n == 1 {
+            (f[0], f[1]) = (fd[0], fd[0]);
+        }
+        f
+    }
+
+    fn interpolate(&self, t: &[f64], z: &[f64], f: &[f64], x: f64) -> f64 {
+        let i = segment(t, x);
+        let (t0, t1) = (t[i - 1], t[i]);
+        let (r0, r1) = (t0 * z[i - 1], t1 * z[i]);
+        let h = t1 - t0;
+        let fd = (r1 - r0) / h;
+        let (g0, g1) = (f[i] - fd, f[i + 1] - fd);
+        (r0 + fd * (x - t0) + h * Self::g_integral(g0, g1, (x - t0) / h)) / x
+    }
+}
+
+/**
+Enum defining the interpolation methods which may be set on a RateCurve
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolator {
+    LinearZero,
+    LogLinearDiscount,
+    FlatForward,
+    NaturalCubic,
+    Hermite,
+    MonotoneConvex,
+}
+
+impl Interpolator {
+    fn method(&self) -> &dyn Interpolation {
+        match self {
+            Self::LinearZero => &LinearZero,
+            Self::LogLinearDiscount => &LogLinearDiscount,
+            Self::FlatForward => &FlatForward,
+            Self::NaturalCubic => &NaturalCubic,
+            Self::Hermite => &Hermite,
+            Self::MonotoneConvex => &MonotoneConvex,
+        }
+    }
+}
+
+impl Interpolation for Interpolator {
+    fn fit(&self, t: &[f64], z: &[f64]) -> Vec<f64> {
+        self.method().fit(t, z)
+    }
+
+    fn interpolate(&self, t: &[f64], z: &[f64], coef: &[f64], x: f64) -> f64 {
+        self.method().interpolate(t, z, coef, x)
+    }
+}
+
+/** Definition of an InterpolatedCurve, from which its pillars and coefficients are built */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InterpolatedCurveDef {
+    curve: RateCurve,
+    interp: Interpolator,
+    extrap: Extrapolation,
+}
+
+impl From<InterpolatedCurveDef> for InterpolatedCurve {
+    fn from(x: InterpolatedCurveDef) -> Self {
+        InterpolatedCurve::new(&x.curve, x.interp, x.extrap)
+    }
+}
+
+impl From<InterpolatedCurve> for InterpolatedCurveDef {
+    fn from(x: InterpolatedCurve) -> Self {
+        InterpolatedCurveDef {
+            curve: x.curve,
+            interp: x.interp,
+            extrap: x.extrap,
+        }
+    }
+}
+
+/**
+InterpolatedCurve : struct defining a RateCurve along with the interpolation and
+extrapolation of its rates. The zero rates at the pillars and the coefficients of the
+interpolation are built once by new, and are not changed afterwards.
+
+- curve     = RateCurve with the rates at the pillars i / freq
+- interp    = Interpolation method
+- extrap    = Extrapolation method
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "InterpolatedCurveDef", into = "InterpolatedCurveDef")]
+pub struct InterpolatedCurve {
+    curve: RateCurve,
+    interp: Interpolator,
+    extrap: Extrapolation,
+    t: Vec<f64>,
+    z: Vec<f64>,
+    coef: Vec<f64>,
+}
+
+impl InterpolatedCurve {
+    /**
+    New InterpolatedCurve on the pillars of a RateCurve
+
+    - curve     = RateCurve, whose own interpolation if any is replaced
+    - interp    = Interpolation method
+    - extrap    = Extrapolation method
+     */
+    pub fn new(curve: &RateCurve, interp: Interpolator, extrap: Extrapolation) -> Self {
+        let curve = curve.pillars().clone();
+        let freq = curve.freq();
+        let z = curve.to_exponential().rates().to_vec();
+        let t: Vec<f64> = (1..=z.len()).map(|i| i as f64 / freq).collect();
+        let coef = if t.len() > 1 {
+            interp.fit(&t, &z)
+        } else {
+            vec![]
+        };
+        InterpolatedCurve {
+            curve,
+            interp,
+            extrap,
+            t,
+            z,
+            coef,
+        }
+    }
+
+    /** RateCurve with the rates at the pillars */
+    pub fn curve(&self) -> &RateCurve {
+        &self.curve
+    }
+
+    /** Interpolation method */
+    pub fn interp(&self) -> Interpolator {
+        self.interp
+    }
+
+    /** Extrapolation method */
+    pub fn extrap(&self) -> Extrapolation {
+        self.extrap
+    }
+
+    /**
+    Continuously compounded zero rate at a time
+
+    - x = time given as period
+     */
+    pub fn zero_rate(&self, x: f64) -> f64 {
+        let (t, z) = (&self.t, &self.z);
+        let n = t.len();
+        let extend = |i: usize, j: usize| match self.extrap {
+            Extrapolation::Flat => z[i],
+            Extrapolation::Linear if n > 1 => z[i] + (z[j] - z[i]) / (t[j] - t[i]) * (x - t[i]),
+            Extrapolation::Linear => z[i],
+        };
+        if x <= t[0] {
+            extend(0, 1)
+        } else if x >= t[n - 1] {
+            extend(n - 1, n.max(2) - 2)
+        } else {
+            self.interp.interpolate(t, z, &self.coef, x)
+        }
+    }
+
+    /**
+    Discount factor at a time
+
+    - x = time given as period
+     */
+    pub fn df(&self, x: f64) -> f64 {
+        (-self.zero_rate(x) * x).exp()
+    }
+
+    /**
+    Rate at a time given in the convention of the RateCurve
+
+    - x = time given as period
+     */
+    pub fn rate_estim(&self, x: f64) -> f64 {
+        let z = self.zero_rate(x);
+        match self.curve {
+            RateCurve::NominalRateCurve { freq, .. } => crate::exp_nom_rate(z, freq),
+            RateCurve::EffectiveRateCurve { .. } => crate::exp_eff_rate(z),
+            _ => z,
+        }
+    }
+
+    /**
+    The Present Value of a cash flow at a particular time.
+
+    - c     = cash flow
+    - tim   = time in period at which the cash flow occurs.
+     */
+    pub fn pv(&self, c: f64, tim: f64) -> f64 {
+        c * self.df(tim)
+    }
+
+    /**
+    Continuously compounded forward rate between two times
+
+    - t0 = start of the forward period
+    - t1 = end of the forward period
+     */
+    pub fn forward_rate(&self, t0: f64, t1: f64) -> f64 {
+        (self.zero_rate(t1) * t1 - self.zero_rate(t0) * t0) / (t1 - t0)
+    }
+
+    /**
+    Resample the curve to a RateCurve of the same type at a new frequency, so that the
+    interpolated rates may be used by the functions taking a RateCurve.
+
+    - freq  = frequency of the new curve
+    - t_max = time till which the curve is needed
+     */
+    pub fn resample(&self, freq: f64, t_max: f64) -> RateCurve {
+        RateCurve::from_zero_fn(freq, t_max, |t| self.zero_rate(t)).to_type_of(&self.curve)
+    }
+}
+
+#[cfg(test)]
+mod interpolations_fn {
+    use super::*;
+    use crate::approx;
+    use crate::fixedincomes::bonds::CouponBond;
+    use crate::fixedincomes::bonds::rates::Rates;
+
+    #[test]
+    fn interpolations() {
+        let rc = RateCurve::ExponentialRateCurve {
+            rate: vec![0.030, 0.035, 0.040, 0.041, 0.042, 0.045],
+            freq: 1.0,
+        };
+        let check = |interp: Interpolator| {
+            let ic = InterpolatedCurve::new(&rc, interp, Extrapolation::Flat);
+            [0.030, 0.035, 0.040, 0.041, 0.042, 0.045]
+                .iter()
+                .enumerate()
+                .for_each(|(i, &z)| assert!(approx(ic.zero_rate((i + 1) as f64), z)));
+            assert!(approx(ic.zero_rate(0.5), 0.030));
+            assert!(approx(ic.zero_rate(10.0), 0.045));
+            ic
+        };
+
+        let lz = check(Interpolator::LinearZero);
+        assert!(approx(lz.zero_rate(2.5), 0.0375));
+        let ll = check(Interpolator::LogLinearDiscount);
+        assert!(approx(ll.forward_rate(2.0, 2.5), 0.05));
+        assert!(approx(ll.forward_rate(2.2, 2.7), 0.05));
+        let ff = check(Interpolator::FlatForward);
+        assert!(approx(
+            1.0 / ff.df(2.5),
+            0.5 * ((0.07f64).exp() + (0.12f64).exp())
+        ));
+        let cs = check(Interpolator::NaturalCubic);
+        let hm = check(Interpolator::Hermite);
+        let mc = check(Interpolator::MonotoneConvex);
+        assert!(approx(cs.zero_rate(3.5), 0.040855263157895));
+        assert!(approx(hm.zero_rate(3.5), 0.04075));
+        assert!(approx(mc.zero_rate(2.5), 0.038028));
+        assert!(approx(mc.zero_rate(5.7), 0.044042894736842));
+        let fwd: Vec<f64> = (1..60)
+            .map(|i| mc.forward_rate(i as f64 * 0.1, i as f64 * 0.1 + 1e-6))
+            .collect();
+        assert!(fwd.iter().all(|&f| f > 0.0));
+        assert!(fwd.windows(2).all(|w| (w[1] - w[0]).abs() < 0.01));
+
+        let lx = InterpolatedCurve::new(&rc, Interpolator::LinearZero, Extrapolation::Linear);
+        assert!(approx(lx.zero_rate(7.0), 0.048));
+        assert!(approx(lx.zero_rate(0.0), 0.025));
+
+        let rs = InterpolatedCurve::new(
+            &rc.to_nominal(),
+            Interpolator::LinearZero,
+            Extrapolation::Flat,
+        )
+        .resample(2.0, 6.0);
+        assert!(approx(rs.pv(1.0, 2.5), lz.df(2.5)));
+        assert!(approx(rs.rate_estim(10.0), crate::exp_nom_rate(0.045, 2.0)));
+
+        let rn = rc
+            .to_nominal()
+            .interpolated(Interpolator::NaturalCubic, Extrapolation::Flat);
+        assert!(approx(rn.pv(1.0, 3.5), cs.df(3.5)));
+        assert!(approx(
+            rn.rate_estim(3.5),
+            crate::exp_nom_rate(cs.zero_rate(3.5), 1.0)
+        ));
+        assert!(approx(
+            rn.shift(0.001).to_exponential().rate_estim(4.0),
+            crate::nom_exp_rate(crate::exp_nom_rate(0.041, 1.0) + 0.001, 1.0)
+        ));
+        let sp = Rates::SpotRates { rate: rn.clone() };
+        assert!(matches!(
+            sp.to_par().curve(),
+            RateCurve::InterpolatedRateCurve(_)
+        ));
+        assert!(approx(
+            sp.to_par().to_spot().curve().pv(1.0, 3.5),
+            cs.df(3.5)
+        ));
+        assert!(approx(
+            sp.forward_rate(2.5, 1.0),
+            crate::exp_nom_rate(cs.forward_rate(2.5, 3.5), 1.0)
+        ));
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let p: f64 = (1..=10)
+            .map(|i| cb.par * (0.025 + if i == 10 { 1.0 } else { 0.0 }) * cs.df(i as f64 * 0.5))
+            .sum();
+        assert!(approx(cb.price_ratecurve(&rn), p));
+        let rx: RateCurve = serde_json::from_str(&serde_json::to_string(&rn).unwrap()).unwrap();
+        assert!(approx(rx.rate_estim(3.5), rn.rate_estim(3.5)));
+    }
+}
@@ -0,0 +1,298 @@
+/*!
+Implement Multi-curve modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::multicurves <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the multi-curve framework, where the cash flows are discounted on
+one curve (say, OIS like SOFR) while the floating rates are projected from a separate
+curve for each index (say, 3M term rate), along with the dual-curve bootstrap of the
+projection curves from par swap rates.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::FloatingRateNotes;
+use super::rates::RateCurve;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/**
+CurveSet : struct defining a set of spot RateCurves keyed by index
+
+- discount  = Index of the curve used for discounting
+- curves    = RateCurves keyed by index, used for discounting and projection
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveSet {
+    pub discount: String,
+    pub curves: HashMap<String, RateCurve>,
+}
+
+impl CurveSet {
+    /**
+    New CurveSet with the discount curve
+
+    - index = Index of the discount curve, e.g. "SOFR"
+    - rc    = Discount RateCurve
+     */
+    pub fn new(index: &str, rc: RateCurve) -> Self {
+        CurveSet {
+            discount: index.to_string(),
+            curves: HashMap::from([(index.to_string(), rc)]),
+        }
+    }
+
+    /**
+    Add or replace the RateCurve of an index
+
+    - index = Index of the curve, e.g. "TERM3M"
+    - rc    = RateCurve
+     */
+    pub fn insert(&mut self, index: &str, rc: RateCurve) {
+        self.curves.insert(index.to_string(), rc);
+    }
+
+    /** RateCurve of an index */
+    pub fn curve(&self, index: &str) -> Option<&RateCurve> {
+        self.curves.get(index)
+    }
+
+    /** Discount RateCurve, if it is in the CurveSet */
+    pub fn discount_curve(&self) -> Option<&RateCurve> {
+        self.curve(&self.discount)
+    }
+
+    /**
+    Discount factor at a time on the discount curve
+
+    - t = time given as period
+     */
+    pub fn df(&self, t: f64) -> Option<f64> {
+        Some(self.discount_curve()?.pv(1.0, t))
+    }
+
+    /**
+    Simple forward rate of an index for a period projected from its curve, or None if
+    the index has no curve
+
+    - index = Index of the projection curve
+    - t     = start of the forward period
+    - tenor = tenor of the forward period
+     */
+    pub fn forward_rate(&self, index: &str, t: f64, tenor: f64) -> Option<f64> {
+        let rc = self.curve(index)?;
+        Some((rc.pv(1.0, t) / rc.pv(1.0, t + tenor) - 1.0) / tenor)
+    }
+
+    /**
+    Annuity of 1 unit of fixed rate paid freq times a period, discounted on the discount
+    curve
+
+    - freq      = Frequency of payment
+    - t_life    = Life of the annuity
+     */
+    pub fn annuity(&self, freq: f64, t_life: f64) -> Option<f64> {
+        (1..=(t_life * freq).round() as usize)
+            .map(|i| self.df(i as f64 / freq))
+            .sum::<Option<f64>>()
+            .map(|x| x / freq)
+    }
+
+    /**
+    PV of 1 unit of notional of a floating leg paying the index plus a spread, with the
+    index projected from its curve and discounted on the discount curve
+
+    - index     = Index of the projection curve
+    - freq      = Frequency of payment and reset
+    - t_life    = Life of the floating leg
+    - spread    = Spread over the index rate
+     */
+    pub fn float_leg(&self, index: &str, freq: f64, t_life: f64, spread: f64) -> Option<f64> {
+        let tenor = 1.0 / freq;
+        (1..=(t_life * freq).round() as usize)
+            .map(|i| {
+                let t = i as f64 * tenor;
+                Some((self.forward_rate(index, t - tenor, tenor)? + spread) * self.df(t)? * tenor)
+            })
+            .sum()
+    }
+
+    /**
+    Par swap rate of a fixed for floating swap on an index
+
+    - index         = Index of the projection curve
+    - fixed_freq    = Frequency of payment of the fixed leg
+    - float_freq    = Frequency of payment and reset of the floating leg
+    - t_life        = Life of the swap
+     */
+    pub fn par_swap_rate(
+        &self,
+        index: &str,
+        fixed_freq: f64,
+        float_freq: f64,
+        t_life: f64,
+    ) -> Option<f64> {
+        Some(self.float_leg(index, float_freq, t_life, 0.0)? / self.annuity(fixed_freq, t_life)?)
+    }
+
+    /**
+    Dual-curve bootstrap of the projection curve of an index from par swap rates, given
+    the discount curve. The projection curve is given as Nominal spot rates at freq, with
+    the rates between the swap maturities interpolated linearly and flat before the first.
+    Returns None if there is no discount curve or a swap rate cannot be matched.
+
+    - index         = Index of the projection curve
+    - freq          = Frequency of the projection curve, which is the reset frequency of the index
+    - swaps         = Vector of (life, par swap rate) in ascending order of life
+    - fixed_freq    = Frequency of payment of the fixed leg
+     */
+    pub fn bootstrap_projection(
+        &mut self,
+        index: &str,
+        freq: f64,
+        swaps: &[(f64, f64)],
+        fixed_freq: f64,
+    ) -> Option<()> {
+        self.discount_curve()?;
+        let n = swaps.last().map_or(0, |x| (x.0 * freq).round() as usize);
+        let mut rate = vec![0.0; n];
+        let mut k0 = 0;
+        for &(t_life, s) in swaps {
+            let k = (t_life * freq).round() as usize;
+            let fill = |rate: &mut Vec<f64>, x: f64| {
+                let r0 = if k0 == 0 { x } else { rate[k0 - 1] };
+                (k0..k)
+                    .for_each(|i| rate[i] = r0 + (x - r0) * (i + 1 - k0) as f64 / (k - k0) as f64);
+            };
+            let x = crate::newt_raph(
+                |x| {
+                    let mut rt = rate.clone();
+                    fill(&mut rt, x);
+                    let mut cs = self.clone();
+                    cs.insert(index, RateCurve::NominalRateCurve { rate: rt, freq });
+                    cs.par_swap_rate(index, fixed_freq, freq, t_life).unwrap() - s
+                },
+                s,
+                1e-12,
+            )?;
+            fill(&mut rate, x);
+            k0 = k;
+        }
+        self.insert(index, RateCurve::NominalRateCurve { rate, freq });
+        Some(())
+    }
+}
+
+impl FloatingRateNotes {
+    /**
+    Price of Floating Rate Note on a CurveSet, or None if the index or the discount curve
+    is missing
+
+    The coupons are projected from the projection curve of the index and are discounted
+    at the forward rates of the discount curve plus the discount margin for each reset
+    period, the same convention as price_ratecurve.
+
+    - cs                = CurveSet
+    - index             = Index of the projection curve
+    - discount_margin   = Discount margin over the discount curve
+     */
+    pub fn price_multicurve(
+        &self,
+        cs: &CurveSet,
+        index: &str,
+        discount_margin: f64,
+    ) -> Option<f64> {
+        let idx = self.projected_index(cs.curve(index)?);
+        let disc = self.projected_index(cs.discount_curve()?);
+        Some(self.pv_projected(&idx, &disc, discount_margin))
+    }
+
+    /**
+    Discount margin of a Floating Rate Note given a CurveSet and a Price
+     */
+    pub fn discount_margin_multicurve(
+        &self,
+        price: f64,
+        cs: &CurveSet,
+        index: &str,
+    ) -> Option<f64> {
+        self.price_multicurve(cs, index, 0.0)?;
+        crate::newt_raph(
+            |x| self.price_multicurve(cs, index, x).unwrap() - price,
+            0.005,
+            1e-6,
+        )
+    }
+}
+
+#[cfg(test)]
+mod multicurves_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn multi_curves() {
+        let ois = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+        let mut cs = CurveSet::new("SOFR", ois.clone());
+        let swaps = vec![(1.0, 0.0260), (2.0, 0.0290), (3.0, 0.0310), (5.0, 0.0335)];
+        cs.bootstrap_projection("TERM3M", 4.0, &swaps, 2.0).unwrap();
+        swaps.iter().for_each(|&(t_life, s)| {
+            let par = cs.par_swap_rate("TERM3M", 2.0, 4.0, t_life).unwrap();
+            assert!(approx(par, s));
+            let fl = cs.float_leg("TERM3M", 4.0, t_life, 0.0).unwrap();
+            assert!((fl - s * cs.annuity(2.0, t_life).unwrap()).abs() < 1e-10);
+        });
+        assert!(
+            cs.forward_rate("TERM3M", 1.0, 0.25).unwrap()
+                > cs.forward_rate("SOFR", 1.0, 0.25).unwrap()
+        );
+        assert!(cs.forward_rate("Y", 1.0, 0.25).is_none());
+        let mut nd = CurveSet::new("SOFR", ois.clone());
+        nd.discount = "ESTR".to_string();
+        assert!(nd.df(1.0).is_none());
+        assert!(
+            nd.bootstrap_projection("TERM3M", 4.0, &swaps, 2.0)
+                .is_none()
+        );
+
+        let frn = FloatingRateNotes::new(100.0, 0.0, 4.0, 5.0);
+        let single = RateCurve::NominalRateCurve {
+            rate: (1..=20).map(|i| 0.02 + 0.0006 * i as f64).collect(),
+            freq: 4.0,
+        };
+        let mut sc = CurveSet::new("SOFR", single.clone());
+        sc.insert("TERM3M", single.clone());
+        assert!(approx(
+            frn.price_multicurve(&sc, "TERM3M", 0.0).unwrap(),
+            100.0
+        ));
+        [0.0, 0.01].iter().for_each(|&dm| {
+            assert!(approx(
+                frn.price_multicurve(&sc, "TERM3M", dm).unwrap(),
+                frn.price_ratecurve(&single, dm)
+            ))
+        });
+        assert!(approx(
+            frn.projected_index(cs.curve("TERM3M").unwrap())[3],
+            cs.forward_rate("TERM3M", 0.75, 0.25).unwrap()
+        ));
+        assert!(frn.price_multicurve(&sc, "Y", 0.0).is_none());
+        let p = frn.price_multicurve(&cs, "TERM3M", 0.0).unwrap();
+        assert!(p > 100.0);
+        assert!(
+            frn.discount_margin_multicurve(p, &cs, "TERM3M")
+                .unwrap()
+                .abs()
+                < 1e-6
+        );
+    }
+}
@@ -0,0 +1,313 @@
+/*!
+Implement Nelson-Siegel and Svensson curve modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::nelsonsiegel <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the parametric yield curves of Nelson-Siegel and Svensson along
+with their least-squares fit to zero yields or bond prices. The rates are given as
+continuously compounded rates.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use super::{CashFlowSchedule, CouponBond};
+use serde::{Deserialize, Serialize};
+
+/** Loading of the slope factor = (1 - exp(-t/tau)) / (t/tau) */
+fn slope_loading(t: f64, tau: f64) -> f64 {
+    let x = t / tau;
+    if x < 1e-9 {
+        1.0
+    } else {
+        (1.0 - (-x).exp()) / x
+    }
+}
+
+/** Loading of the curvature factor = (1 - exp(-t/tau)) / (t/tau) - exp(-t/tau) */
+fn curvature_loading(t: f64, tau: f64) -> f64 {
+    slope_loading(t, tau) - (-t / tau).exp()
+}
+
+/**
+Trait for parametric yield curves fitted by least squares
+ */
+pub trait ParametricCurve: Sized {
+    /** Continuously compounded zero rate at time t */
+    fn zero_rate(&self, t: f64) -> f64;
+
+    /** Instantaneous forward rate at time t */
+    fn forward_rate(&self, t: f64) -> f64;
+
+    /** Curve parameters as a vector, with the decay factors given as their logs */
+    fn to_params(&self) -> Vec<f64>;
+
+    /** Curve from the parameters given as a vector */
+    fn from_params(x: &[f64]) -> Self;
+
+    /** Discount factor at time t */
+    fn df(&self, t: f64) -> f64 {
+        (-self.zero_rate(t) * t).exp()
+    }
+
+    /**
+    Sample the curve as Nominal spot rates at a regular grid i / freq
+
+    - freq  = frequency of the RateCurve
+    - t_max = time till which the RateCurve is needed
+     */
+    fn to_ratecurve(&self, freq: f64, t_max: f64) -> RateCurve {
+        RateCurve::from_zero_fn(freq, t_max, |t| self.zero_rate(t))
+    }
+
+    /**
+    Price of a Coupon bond on the curve
+
+    - cb = CouponBond
+     */
+    fn price_bond(&self, cb: &CouponBond) -> f64 {
+        cb.cashflow_schedule()
+            .iter()
+            .map(|&(t, c)| c * self.df(t))
+            .sum()
+    }
+
+    /**
+    Least-squares fit of the curve to zero yields
+
+    - yields    = Vector of (time, continuously compounded zero yield)
+    - x0        = Initial guess of the curve
+
+    Returns None if the solver fails
+     */
+    fn fit_yields(yields: &[(f64, f64)], x0: &Self) -> Option<Self> {
+        let x = crate::minimize(
+            |x| {
+                let c = Self::from_params(x);
+                yields
+                    .iter()
+                    .map(|&(t, y)| (c.zero_rate(t) - y).powi(2))
+                    .sum()
+            },
+            &x0.to_params(),
+            0.1,
+        )?;
+        Some(Self::from_params(&x))
+    }
+
+    /**
+    Least-squares fit of the curve to bond prices, with the errors weighted by par
+
+    - bonds = Vector of (CouponBond, price)
+    - x0    = Initial guess of the curve
+
+    Returns None if the solver fails
+     */
+    fn fit_prices(bonds: &[(CouponBond, f64)], x0: &Self) -> Option<Self> {
+        let x = crate::minimize(
+            |x| {
+                let c = Self::from_params(x);
+                bonds
+                    .iter()
+                    .map(|(cb, p)| ((c.price_bond(cb) - p) / cb.par).powi(2))
+                    .sum()
+            },
+            &x0.to_params(),
+            0.1,
+        )?;
+        Some(Self::from_params(&x))
+    }
+}
+
+/**
+NelsonSiegel : struct defining the Nelson-Siegel curve
+z(t) = beta0 + beta1 * L1(t/tau) + beta2 * L2(t/tau)
+
+- beta0 = Level, the long end zero rate
+- beta1 = Slope, where beta0 + beta1 is the short end zero rate
+- beta2 = Curvature
+- tau   = Decay factor
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NelsonSiegel {
+    pub beta0: f64,
+    pub beta1: f64,
+    pub beta2: f64,
+    pub tau: f64,
+}
+
+impl ParametricCurve for NelsonSiegel {
+    fn zero_rate(&self, t: f64) -> f64 {
+        self.beta0
+            + self.beta1 * slope_loading(t, self.tau)
+            + self.beta2 * curvature_loading(t, self.tau)
+    }
+
+    fn forward_rate(&self, t: f64) -> f64 {
+        let x = t / self.tau;
+        self.beta0 + (self.beta1 + self.beta2 * x) * (-x).exp()
+    }
+
+    fn to_params(&self) -> Vec<f64> {
+        vec![self.beta0, self.beta1, self.beta2, self.tau.ln()]
+    }
+
+    fn from_params(x: &[f64]) -> Self {
+        NelsonSiegel {
+            beta0: x[0],
+            beta1: x[1],
+            beta2: x[2],
+            tau: x[3].exp(),
+        }
+    }
+}
+
+/**
+Svensson : struct defining the Svensson curve, which adds a second curvature term to
+the Nelson-Siegel curve
+z(t) = beta0 + beta1 * L1(t/tau1) + beta2 * L2(t/tau1) + beta3 * L2(t/tau2)
+
+- beta0 = Level, the long end zero rate
+- beta1 = Slope, where beta0 + beta1 is the short end zero rate
+- beta2 = First curvature
+- beta3 = Second curvature
+- tau1  = First decay factor
+- tau2  = Second decay factor
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Svensson {
+    pub beta0: f64,
+    pub beta1: f64,
+    pub beta2: f64,
+    pub beta3: f64,
+    pub tau1: f64,
+    pub tau2: f64,
+}
+
+impl ParametricCurve for Svensson {
+    fn zero_rate(&self, t: f64) -> f64 {
+        self.beta0
+            + self.beta1 * slope_loading(t, self.tau1)
+            + self.beta2 * curvature_loading(t, self.tau1)
+            + self.beta3 * curvature_loading(t, self.tau2)
+    }
+
+    fn forward_rate(&self, t: f64) -> f64 {
+        let (x1, x2) = (t / self.tau1, t / self.tau2);
+        self.beta0 + (self.beta1 + self.beta2 * x1) * (-x1).exp() + self.beta3 * x2 * (-x2).exp()
+    }
+
+    fn to_params(&self) -> Vec<f64> {
+        vec![
+            self.beta0,
+            self.beta1,
+            self.beta2,
+            self.beta3,
+            self.tau1.ln(),
+            self.tau2.ln(),
+        ]
+    }
+
+    fn from_params(x: &[f64]) -> Self {
+        Svensson {
+            beta0: x[0],
+            beta1: x[1],
+            beta2: x[2],
+            beta3: x[3],
+            tau1: x[4].exp(),
+            tau2: x[5].exp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod nelsonsiegel_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn parametric_curves() {
+        let ns = NelsonSiegel {
+            beta0: 0.045,
+            beta1: -0.015,
+            beta2: 0.02,
+            tau: 2.0,
+        };
+        assert!(approx(ns.zero_rate(0.0), 0.03));
+        assert!(approx(ns.forward_rate(0.0), 0.03));
+        let (t, dt) = (3.0, 1e-5);
+        assert!(
+            (ns.forward_rate(t)
+                - (ns.zero_rate(t + dt) * (t + dt) - ns.zero_rate(t - dt) * (t - dt)) / (2.0 * dt))
+                .abs()
+                < 1e-8
+        );
+
+        let yields: Vec<(f64, f64)> = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0]
+            .iter()
+            .map(|&t| (t, ns.zero_rate(t)))
+            .collect();
+        let x0 = NelsonSiegel {
+            beta0: 0.04,
+            beta1: -0.01,
+            beta2: 0.0,
+            tau: 1.0,
+        };
+        let nf = NelsonSiegel::fit_yields(&yields, &x0).unwrap();
+        yields
+            .iter()
+            .for_each(|&(t, y)| assert!((nf.zero_rate(t) - y).abs() < 1e-6));
+
+        let bonds: Vec<(CouponBond, f64)> = [(0.03, 2.0), (0.04, 5.0), (0.045, 10.0), (0.05, 20.0)]
+            .iter()
+            .map(|&(c, t_life)| {
+                let cb = CouponBond {
+                    par: 100.0,
+                    c,
+                    freq: 2.0,
+                    t_life,
+                };
+                (cb, ns.price_bond(&cb))
+            })
+            .collect();
+        let nb = NelsonSiegel::fit_prices(&bonds, &x0).unwrap();
+        bonds
+            .iter()
+            .for_each(|(cb, p)| assert!((nb.price_bond(cb) - p).abs() < 1e-3));
+
+        let sv = Svensson {
+            beta0: 0.045,
+            beta1: -0.015,
+            beta2: 0.02,
+            beta3: -0.01,
+            tau1: 2.0,
+            tau2: 8.0,
+        };
+        let yields: Vec<(f64, f64)> = yields.iter().map(|&(t, _)| (t, sv.zero_rate(t))).collect();
+        let sf = Svensson::fit_yields(
+            &yields,
+            &Svensson {
+                beta0: 0.04,
+                beta1: -0.01,
+                beta2: 0.0,
+                beta3: 0.0,
+                tau1: 1.0,
+                tau2: 5.0,
+            },
+        )
+        .unwrap();
+        yields
+            .iter()
+            .for_each(|&(t, y)| assert!((sf.zero_rate(t) - y).abs() < 1e-5));
+
+        let cb = bonds[2].0;
+        assert!(approx(
+            cb.price_ratecurve(&sv.to_ratecurve(2.0, 10.0)),
+            sv.price_bond(&cb)
+        ));
+    }
+}
@@ -0,0 +1,277 @@
+/*!
+Implement Yield curve PCA modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::pca <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the principal component analysis of the historical changes of a
+yield curve, giving the level, slope and curvature factors with their explained variance
+and scores, along with the scenarios and hedge ratios built on the factors.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use super::scenarios::{CurveShock, Revaluation};
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+use ndarray::{Array1, Array2, Axis};
+
+/**
+CurvePCA : struct defining the principal components of the changes of a yield curve
+
+- tenors        = Tenors at which the curves are sampled
+- mean          = Mean change of the rate at each tenor
+- loadings      = Loadings of each factor in the columns, with tenors in the rows
+- variance      = Variance of each factor, i.e. the eigenvalues in descending order
+- explained     = Fraction of the total variance explained by each factor
+- scores        = Scores of each factor in the columns, with the changes in the rows
+ */
+#[derive(Debug, Clone)]
+pub struct CurvePCA {
+    pub tenors: Vec<f64>,
+    pub mean: Array1<f64>,
+    pub loadings: Array2<f64>,
+    pub variance: Vec<f64>,
+    pub explained: Vec<f64>,
+    pub scores: Array2<f64>,
+}
+
+impl CurvePCA {
+    /**
+    Fit the principal components to the changes between consecutive curve snapshots. The
+    sign of each factor is set so that level is a rise, slope a steepening and curvature a
+    rise in the belly.
+
+    - curves    = Time series of RateCurve snapshots of the same type
+    - tenors    = Tenors at which the curves are sampled
+
+    Returns None if there are less than 3 snapshots, i.e. less than 2 changes, no tenors
+    or no variance in the changes.
+     */
+    pub fn fit(curves: &[RateCurve], tenors: &[f64]) -> Option<Self> {
+        let n = tenors.len();
+        if curves.len() < 3 || n == 0 {
+            return None;
+        }
+        let levels =
+            Array2::from_shape_fn((curves.len(), n), |(i, j)| curves[i].rate_estim(tenors[j]));
+        let changes = &levels.slice(ndarray::s![1.., ..]) - &levels.slice(ndarray::s![..-1, ..]);
+        let mean = changes.mean_axis(Axis(0))?;
+        let x = &changes - &mean;
+        let cov = x.t().dot(&x) / (x.nrows() as f64 - 1.0);
+
+        let eig = SymmetricEigen::new(DMatrix::from_fn(n, n, |i, j| cov[[i, j]]));
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eig.eigenvalues[b].total_cmp(&eig.eigenvalues[a]));
+
+        let mid = n / 2;
+        let loadings = Array2::from_shape_fn((n, n), |(i, k)| {
+            let v = eig.eigenvectors.column(order[k]);
+            let s = match k {
+                0 => v.sum(),
+                1 => v[n - 1] - v[0],
+                _ => v[mid] - (v[0] + v[n - 1]) / 2.0,
+            };
+            if s < 0.0 { -v[i] } else { v[i] }
+        });
+        let variance: Vec<f64> = order.iter().map(|&k| eig.eigenvalues[k].max(0.0)).collect();
+        let total: f64 = variance.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        Some(CurvePCA {
+            tenors: tenors.to_vec(),
+            scores: x.dot(&loadings),
+            explained: variance.iter().map(|v| v / total).collect(),
+            variance,
+            mean,
+            loadings,
+        })
+    }
+
+    /** Loadings of factor k at each tenor */
+    pub fn factor(&self, k: usize) -> Vec<f64> {
+        self.loadings.column(k).to_vec()
+    }
+
+    /** Loadings of the level factor */
+    pub fn level(&self) -> Vec<f64> {
+        self.factor(0)
+    }
+
+    /** Loadings of the slope factor */
+    pub fn slope(&self) -> Vec<f64> {
+        self.factor(1)
+    }
+
+    /** Loadings of the curvature factor */
+    pub fn curvature(&self) -> Vec<f64> {
+        self.factor(2)
+    }
+
+    /**
+    Shock to the curve for moves of the factors given in their standard deviations,
+    piecewise linear between the tenors
+
+    - moves = Move of each factor in standard deviations, starting from level
+     */
+    pub fn shock(&self, moves: &[f64]) -> CurveShock {
+        CurveShock::Custom(
+            self.tenors
+                .iter()
+                .enumerate()
+                .map(|(i, &t)| {
+                    let s = moves
+                        .iter()
+                        .enumerate()
+                        .map(|(k, m)| m * self.variance[k].sqrt() * self.loadings[[i, k]])
+                        .sum();
+                    (t, s)
+                })
+                .collect(),
+        )
+    }
+
+    /**
+    Curve under a PCA scenario
+
+    - rc    = Base RateCurve
+    - moves = Move of each factor in standard deviations, starting from level
+     */
+    pub fn scenario(&self, rc: &RateCurve, moves: &[f64]) -> RateCurve {
+        rc.shock(&self.shock(moves))
+    }
+
+    /**
+    Change in value of an instrument for a move of 1 standard deviation in each of the
+    first n factors
+
+    - rc    = Base RateCurve
+    - x     = Instrument
+    - n     = Number of factors
+     */
+    pub fn factor_exposures(&self, rc: &RateCurve, x: &dyn Revaluation, n: usize) -> Vec<f64> {
+        let v0 = x.value(rc);
+        (0..n)
+            .map(|k| {
+                let mut moves = vec![0.0; k + 1];
+                moves[k] = 1.0;
+                x.value(&self.scenario(rc, &moves)) - v0
+            })
+            .collect()
+    }
+
+    /**
+    Quantities of the hedge instruments which make the position neutral to the first
+    factors, one factor for each hedge instrument
+
+    - rc        = Base RateCurve
+    - target    = Position to be hedged
+    - hedges    = Hedge instruments
+
+    Returns None if the exposures of the hedge instruments are not independent.
+     */
+    pub fn hedge_ratios(
+        &self,
+        rc: &RateCurve,
+        target: &dyn Revaluation,
+        hedges: &[&dyn Revaluation],
+    ) -> Option<Vec<f64>> {
+        let n = hedges.len();
+        let ex: Vec<Vec<f64>> = hedges
+            .iter()
+            .map(|h| self.factor_exposures(rc, *h, n))
+            .collect();
+        let a = DMatrix::from_fn(n, n, |k, i| ex[i][k]);
+        let b = -DVector::from_vec(self.factor_exposures(rc, target, n));
+        Some(a.lu().solve(&b)?.iter().copied().collect())
+    }
+}
+
+#[cfg(test)]
+mod pca_fn {
+    use super::*;
+    use crate::approx;
+    use crate::fixedincomes::bonds::CouponBond;
+
+    #[test]
+    fn curve_pca() {
+        let mut rng = crate::Generator::new(7);
+        let mut rnd = || rng.uniform() - 0.5;
+        let base: Vec<f64> = (1..=20).map(|i| 0.02 + 0.0008 * i as f64).collect();
+        let curves: Vec<RateCurve> = (0..250)
+            .scan((0.0, 0.0, 0.0), |(l, s, c), _| {
+                *l += 0.002 * rnd();
+                *s += 0.001 * rnd();
+                *c += 0.0004 * rnd();
+                let (l, s, c) = (*l, *s, *c);
+                Some(RateCurve::NominalRateCurve {
+                    rate: base
+                        .iter()
+                        .enumerate()
+                        .map(|(i, r)| {
+                            let x = (i + 1) as f64 / 20.0;
+                            r + l
+                                + s * (x - 0.5)
+                                + c * (1.0 - 4.0 * (x - 0.5).powi(2))
+                                + 0.00002 * rnd()
+                        })
+                        .collect(),
+                    freq: 2.0,
+                })
+            })
+            .collect();
+        let tenors = [0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0];
+        let pca = CurvePCA::fit(&curves, &tenors).unwrap();
+        assert!(CurvePCA::fit(&curves[..2], &tenors).is_none());
+
+        assert!(approx(pca.explained.iter().sum::<f64>(), 1.0));
+        assert!(pca.explained[0] > 0.8 && pca.explained[..3].iter().sum::<f64>() > 0.99);
+        assert!(pca.level().iter().all(|&x| x > 0.0));
+        assert!(pca.slope()[6] > 0.0 && pca.slope()[0] < 0.0);
+        assert!(pca.curvature()[3] > 0.0);
+        let g = pca.loadings.t().dot(&pca.loadings);
+        assert!(
+            (0..7)
+                .all(|i| (0..7).all(|j| (g[[i, j]] - if i == j { 1.0 } else { 0.0 }).abs() < 1e-9))
+        );
+        assert!(approx(pca.scores.column(0).var(1.0), pca.variance[0]));
+
+        let rc = curves[0].clone();
+        let up = pca.scenario(&rc, &[2.0]);
+        assert!(approx(
+            up.rate_estim(5.0) - rc.rate_estim(5.0),
+            2.0 * pca.variance[0].sqrt() * pca.loadings[[4, 0]]
+        ));
+
+        let bond = |c: f64, t_life: f64| CouponBond {
+            par: 100.0,
+            c,
+            freq: 2.0,
+            t_life,
+        };
+        let (target, h2, h10) = (bond(0.04, 5.0), bond(0.03, 2.0), bond(0.045, 10.0));
+        let h = pca.hedge_ratios(&rc, &target, &[&h2, &h10]).unwrap();
+        assert!(pca.hedge_ratios(&rc, &target, &[&h2, &h2]).is_none());
+        let ex = [
+            (&target as &dyn Revaluation, 1.0),
+            (&h2, h[0]),
+            (&h10, h[1]),
+        ]
+        .iter()
+        .map(|(x, q)| {
+            pca.factor_exposures(&rc, *x, 2)
+                .iter()
+                .map(|e| q * e)
+                .collect()
+        })
+        .fold(vec![0.0; 2], |s: Vec<f64>, e: Vec<f64>| {
+            vec![s[0] + e[0], s[1] + e[1]]
+        });
+        assert!(ex.iter().all(|e| e.abs() < 1e-9));
+        assert!(h[0] < 0.0 && h[1] < 0.0);
+    }
+}
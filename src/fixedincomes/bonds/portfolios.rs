@@ -0,0 +1,332 @@
+/*!
+Implement Bond Portfolio modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::portfolios <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the analytics of a portfolio of bonds like market value, duration,
+convexity, cash flow ladder and yield along with immunization and cash flow matching
+of a liability stream.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use super::{CashFlowSchedule, CouponBond, durations};
+use serde::{Deserialize, Serialize};
+
+/**
+PV of a stream of cash flows on a RateCurve
+
+- cf    = cash flows given as vector of (time in period, cash flow)
+- rc    = discount RateCurve
+ */
+pub fn pv_cashflows(cf: &[(f64, f64)], rc: &RateCurve) -> f64 {
+    cf.iter().map(|&(t, c)| rc.pv(c, t)).sum()
+}
+
+/**
+Effective duration of a stream of cash flows for a parallel shift of the RateCurve
+ */
+pub fn duration_cashflows(cf: &[(f64, f64)], rc: &RateCurve) -> f64 {
+    durations::effective_duration(|s| pv_cashflows(cf, &rc.shift(s)), 0.0, 1e-4)
+}
+
+/**
+Effective convexity of a stream of cash flows for a parallel shift of the RateCurve
+ */
+pub fn convexity_cashflows(cf: &[(f64, f64)], rc: &RateCurve) -> f64 {
+    durations::effective_convexity(|s| pv_cashflows(cf, &rc.shift(s)), 0.0, 1e-4)
+}
+
+/**
+BondPortfolio : struct defining a portfolio of bonds
+
+- positions = Vector of (bond, quantity) where quantity is the nos of bonds held
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondPortfolio<B: CashFlowSchedule = CouponBond> {
+    pub positions: Vec<(B, f64)>,
+}
+
+impl<B: CashFlowSchedule + Clone> BondPortfolio<B> {
+    /**
+    Aggregate cash flows of the portfolio sorted by time
+     */
+    pub fn cashflows(&self) -> Vec<(f64, f64)> {
+        let mut cf: Vec<(f64, f64)> = self
+            .positions
+            .iter()
+            .flat_map(|(b, q)| {
+                b.cashflow_schedule()
+                    .into_iter()
+                    .map(move |(t, c)| (t, c * q))
+            })
+            .collect();
+        cf.sort_by(|x, y| x.0.total_cmp(&y.0));
+        cf.into_iter()
+            .fold(vec![], |mut v: Vec<(f64, f64)>, (t, c)| {
+                match v.last_mut() {
+                    Some(x) if (x.0 - t).abs() < 1e-9 => x.1 += c,
+                    _ => v.push((t, c)),
+                }
+                v
+            })
+    }
+
+    /**
+    Market value of each position on a RateCurve
+     */
+    pub fn position_values(&self, rc: &RateCurve) -> Vec<f64> {
+        self.positions
+            .iter()
+            .map(|(b, q)| q * pv_cashflows(&b.cashflow_schedule(), rc))
+            .collect()
+    }
+
+    /**
+    Aggregate market value of the portfolio on a RateCurve
+     */
+    pub fn market_value(&self, rc: &RateCurve) -> f64 {
+        self.position_values(rc).iter().sum()
+    }
+
+    /** Market value weighted average of a measure of each bond */
+    fn weighted(&self, rc: &RateCurve, f: impl Fn(&[(f64, f64)]) -> f64) -> f64 {
+        let mv = self.position_values(rc);
+        let tot: f64 = mv.iter().sum();
+        self.positions
+            .iter()
+            .zip(&mv)
+            .map(|((b, _), v)| v / tot * f(&b.cashflow_schedule()))
+            .sum()
+    }
+
+    /**
+    Market value weighted effective duration of the portfolio
+     */
+    pub fn duration(&self, rc: &RateCurve) -> f64 {
+        self.weighted(rc, |cf| duration_cashflows(cf, rc))
+    }
+
+    /**
+    Market value weighted effective convexity of the portfolio
+     */
+    pub fn convexity(&self, rc: &RateCurve) -> f64 {
+        self.weighted(rc, |cf| convexity_cashflows(cf, rc))
+    }
+
+    /**
+    Cash flow ladder with the cash flows summed in buckets (0, b], (b, 2b], ...
+
+    - bucket    = width of each bucket in period
+     */
+    pub fn cashflow_ladder(&self, bucket: f64) -> Vec<f64> {
+        let cf = self.cashflows();
+        let n = cf
+            .last()
+            .map_or(0, |&(t, _)| (t / bucket - 1e-9).ceil() as usize);
+        let mut ld = vec![0.0; n];
+        cf.iter()
+            .for_each(|&(t, c)| ld[((t / bucket - 1e-9).ceil() as usize).max(1) - 1] += c);
+        ld
+    }
+
+    /**
+    Yield of the portfolio, given as the effective rate (IRR) at which the
+    aggregate cash flows equal the market value
+
+    Returns None if the IRR can not be solved.
+     */
+    pub fn yield_estimate(&self, rc: &RateCurve) -> Option<f64> {
+        let (mut tim, mut cf): (Vec<f64>, Vec<f64>) = self.cashflows().into_iter().unzip();
+        tim.insert(0, 0.0);
+        cf.insert(0, -self.market_value(rc));
+        crate::irr(&tim, &cf)
+    }
+
+    /**
+    Immunize a liability stream by a portfolio of the bonds. The quantities are
+    solved such that the market value and the dollar duration of the portfolio
+    match those of the liabilities. Short positions are not allowed.
+
+    - bonds         = bonds available for the portfolio
+    - liabilities   = liabilities given as vector of (time in period, amount)
+    - rc            = discount RateCurve
+
+    Returns None if the solver fails.
+     */
+    pub fn immunize(bonds: &[B], liabilities: &[(f64, f64)], rc: &RateCurve) -> Option<Self> {
+        let pv_l = pv_cashflows(liabilities, rc);
+        let dd_l = pv_l * duration_cashflows(liabilities, rc);
+        let (pv, dd): (Vec<f64>, Vec<f64>) = bonds
+            .iter()
+            .map(|b| {
+                let cf = b.cashflow_schedule();
+                let p = pv_cashflows(&cf, rc);
+                (p, p * duration_cashflows(&cf, rc))
+            })
+            .unzip();
+
+        let x0: Vec<f64> = pv
+            .iter()
+            .map(|p| (pv_l / (bonds.len() as f64 * p)).sqrt())
+            .collect();
+        let x = crate::minimize(
+            |x| {
+                let q = x.iter().map(|v| v * v);
+                let pv_a: f64 = q.clone().zip(&pv).map(|(q, p)| q * p).sum();
+                let dd_a: f64 = q.zip(&dd).map(|(q, d)| q * d).sum();
+                ((pv_a - pv_l) / pv_l).powi(2) + ((dd_a - dd_l) / dd_l).powi(2)
+            },
+            &x0,
+            0.1,
+        )?;
+
+        Some(BondPortfolio {
+            positions: bonds.iter().cloned().zip(x.iter().map(|v| v * v)).collect(),
+        })
+    }
+
+    /**
+    Match a liability stream by the cash flows of a portfolio of the bonds at the
+    minimum cost. The cumulative cash flow of the portfolio, with surplus carried
+    forward without interest, covers the cumulative liability at each liability date.
+    Short positions are not allowed.
+
+    - bonds         = bonds available for the portfolio
+    - liabilities   = liabilities given as vector of (time in period, amount)
+    - rc            = discount RateCurve
+
+    Returns None if the solver fails.
+     */
+    pub fn cashflow_match(bonds: &[B], liabilities: &[(f64, f64)], rc: &RateCurve) -> Option<Self> {
+        let pv_l = pv_cashflows(liabilities, rc);
+        let tot_l: f64 = liabilities.iter().map(|x| x.1).sum();
+        let cfs: Vec<Vec<(f64, f64)>> = bonds.iter().map(|b| b.cashflow_schedule()).collect();
+        let pv: Vec<f64> = cfs.iter().map(|cf| pv_cashflows(cf, rc)).collect();
+
+        // (cumulative asset cash flow per bond, cumulative liability) at each liability date
+        let cum: Vec<(Vec<f64>, f64)> = liabilities
+            .iter()
+            .map(|&(tl, _)| {
+                (
+                    cfs.iter()
+                        .map(|cf| cf.iter().filter(|x| x.0 <= tl + 1e-9).map(|x| x.1).sum())
+                        .collect(),
+                    liabilities.iter().filter(|x| x.0 <= tl).map(|x| x.1).sum(),
+                )
+            })
+            .collect();
+        let cover = |q: &[f64]| -> Vec<f64> {
+            cum.iter()
+                .map(|(a, l)| a.iter().zip(q).map(|(a, q)| a * q).sum::<f64>() / l)
+                .collect()
+        };
+
+        let x0: Vec<f64> = vec![(tot_l / (bonds.len() as f64 * 100.0)).sqrt(); bonds.len()];
+        let x = crate::minimize(
+            |x| {
+                let q: Vec<f64> = x.iter().map(|v| v * v).collect();
+                let cost: f64 = q.iter().zip(&pv).map(|(q, p)| q * p).sum();
+                let short: f64 = cover(&q).iter().map(|c| (1.0 - c).max(0.0).powi(2)).sum();
+                cost / pv_l + 1e4 * short
+            },
+            &x0,
+            0.1,
+        )?;
+
+        let q: Vec<f64> = x.iter().map(|v| v * v).collect();
+        let sc = cover(&q).iter().fold(1.0, |m: f64, c| m.max(1.0 / c));
+        Some(BondPortfolio {
+            positions: bonds
+                .iter()
+                .cloned()
+                .zip(q.iter().map(|v| v * sc))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod portfolios_fn {
+    use super::*;
+    use crate::approx;
+    use crate::fixedincomes::bonds::XCouponBond;
+
+    #[test]
+    fn bond_portfolios() {
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+                0.0335, 0.0340, 0.0345, 0.0350, 0.0355, 0.0360, 0.0365, 0.0370, 0.0375, 0.0380,
+            ],
+            freq: 2.0,
+        };
+        let cb = |c, t_life| CouponBond {
+            par: 100.0,
+            c,
+            freq: 2.0,
+            t_life,
+        };
+
+        let bp = BondPortfolio {
+            positions: vec![(cb(0.04, 2.0), 10.0), (cb(0.05, 5.0), 20.0)],
+        };
+        let mv = bp.market_value(&rc);
+        assert!(approx(
+            mv,
+            10.0 * cb(0.04, 2.0).price_ratecurve(&rc) + 20.0 * cb(0.05, 5.0).price_ratecurve(&rc)
+        ));
+        assert_eq!(bp.cashflows().len(), 10);
+        let ld = bp.cashflow_ladder(1.0);
+        assert_eq!(ld.len(), 5);
+        assert!(approx(ld[1], 1000.0 + 40.0 + 100.0));
+        assert!(approx(ld[4], 2000.0 + 100.0));
+        assert!(approx(bp.duration(&rc), 3.6221431499570613));
+        assert!(approx(bp.convexity(&rc), 17.183250733524957));
+        let (tim, cf): (Vec<f64>, Vec<f64>) = bp.cashflows().into_iter().unzip();
+        assert!(approx(
+            crate::npv_t0(bp.yield_estimate(&rc).unwrap(), &tim, &cf),
+            mv
+        ));
+
+        let dt = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let xb = XCouponBond {
+            par: 100.0,
+            c: 0.04,
+            freq: 2.0,
+            dt_maturity: dt(2026, 1, 15),
+        };
+        let xp = BondPortfolio {
+            positions: vec![((xb, dt(2024, 1, 15)), 10.0)],
+        };
+        assert!(approx(
+            xp.market_value(&rc),
+            10.0 * cb(0.04, 2.0).price_ratecurve(&rc)
+        ));
+        let cf = (xb, dt(2024, 4, 15)).cashflow_schedule();
+        assert_eq!(cf.len(), 4);
+        assert!(approx(cf[0].0, 91.0 / 182.0 / 2.0) && approx(cf[3].1, 102.0));
+
+        let liab = vec![(3.0, 5000.0), (4.0, 6000.0)];
+        let bonds = vec![cb(0.03, 1.0), cb(0.05, 10.0)];
+        let im = BondPortfolio::immunize(&bonds, &liab, &rc).unwrap();
+        assert!(approx(im.market_value(&rc), pv_cashflows(&liab, &rc)));
+        assert!((im.duration(&rc) - duration_cashflows(&liab, &rc)).abs() < 1e-4);
+        assert!(im.convexity(&rc) > convexity_cashflows(&liab, &rc));
+
+        let bonds = vec![cb(0.03, 1.0), cb(0.04, 3.0), cb(0.05, 4.0), cb(0.05, 5.0)];
+        let cm = BondPortfolio::cashflow_match(&bonds, &liab, &rc).unwrap();
+        assert!(cm.positions[0].1 < 1e-6 && cm.positions[3].1 < 1e-6);
+        let cf = cm.cashflows();
+        liab.iter().for_each(|&(tl, _)| {
+            let a: f64 = cf.iter().filter(|x| x.0 <= tl).map(|x| x.1).sum();
+            let l: f64 = liab.iter().filter(|x| x.0 <= tl).map(|x| x.1).sum();
+            assert!(a >= l - 1e-6);
+        });
+    }
+}
@@ -12,6 +12,11 @@ The module describes the base modules of Fixed Incomes Rates.
 You may see the github repository at <https://github.com/n-kishaloy/financelib>
 */
 
+use super::forwardcurves::Compounding;
+use super::interpolations::{Extrapolation, InterpolatedCurve, Interpolator};
+use crate::FileError;
+use serde::{Deserialize, Serialize};
+
 /**
 RateCurve defines Enum for different type of Rates (Nominal, Effective, Exponential)
 given as curve.
@@ -25,18 +30,27 @@ of Rates given in regular intervals. It can be of 3 types:
 Each type has 2 fields
 - rate  = Vector of rates
 - freq  = freq at which the rates are being given per period.
+
+The rates between the points are interpolated linearly. Any of the 3 types may be given
+another interpolation by RateCurve::interpolated, which gives an InterpolatedRateCurve
+used by all the functions in place of the linear interpolation.
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RateCurve {
     NominalRateCurve { rate: Vec<f64>, freq: f64 },
     EffectiveRateCurve { rate: Vec<f64>, freq: f64 },
     ExponentialRateCurve { rate: Vec<f64>, freq: f64 },
+    InterpolatedRateCurve(Box<InterpolatedCurve>),
 }
 
 impl RateCurve {
     /**
     Estimate the rate at a particular time by interpolating between the rate curves points
 
+    The rate before the first point of the curve is taken as the first rate and the
+    rate after the last point as the last rate, unless the curve is interpolated by
+    RateCurve::interpolated with other interpolation and extrapolation methods.
+
     - y = the time given as period whose rate is being sought.
      */
     pub fn rate_estim(&self, y: f64) -> f64 {
@@ -46,6 +60,12 @@ impl RateCurve {
                 (sl as usize, x - sl)
             };
             let (fl, pf) = proper(y * fq);
+            if fl == 0 {
+                return rx[0];
+            }
+            if fl >= rx.len() {
+                return rx[rx.len() - 1];
+            }
             let r0 = rx[fl - 1];
             if pf < 1e-9 {
                 r0
@@ -57,6 +77,7 @@ impl RateCurve {
             Self::NominalRateCurve { rate, freq } => estima(rate, *freq, y),
             Self::EffectiveRateCurve { rate, freq } => estima(rate, *freq, y),
             Self::ExponentialRateCurve { rate, freq } => estima(rate, *freq, y),
+            Self::InterpolatedRateCurve(ic) => ic.rate_estim(y),
         }
     }
 
@@ -72,6 +93,72 @@ impl RateCurve {
             Self::NominalRateCurve { rate: _, freq: f } => crate::pvm(rate, tim, *f, c),
             Self::EffectiveRateCurve { rate: _, freq: _ } => crate::pv(rate, tim, c),
             Self::ExponentialRateCurve { rate: _, freq: _ } => crate::pvc(rate, tim, c),
+            Self::InterpolatedRateCurve(ic) => ic.pv(c, tim),
+        }
+    }
+
+    /**
+    RateCurve with another interpolation and extrapolation of its zero rates between
+    and beyond the points
+
+    - interp    = Interpolation method
+    - extrap    = Extrapolation method
+     */
+    pub fn interpolated(&self, interp: Interpolator, extrap: Extrapolation) -> RateCurve {
+        Self::InterpolatedRateCurve(Box::new(InterpolatedCurve::new(self, interp, extrap)))
+    }
+
+    /**
+    RateCurve with the points of this curve and the interpolation of another curve
+
+    - like  = RateCurve whose interpolation is sought
+     */
+    pub fn interpolated_as(&self, like: &RateCurve) -> RateCurve {
+        match like {
+            Self::InterpolatedRateCurve(ic) => self.interpolated(ic.interp(), ic.extrap()),
+            _ => self.pillars().clone(),
+        }
+    }
+
+    /** RateCurve with the rates at the points, without any other interpolation */
+    pub fn pillars(&self) -> &RateCurve {
+        match self {
+            Self::InterpolatedRateCurve(ic) => ic.curve(),
+            _ => self,
+        }
+    }
+
+    /** Rates at the points of the RateCurve */
+    pub fn rates(&self) -> &[f64] {
+        match self.pillars() {
+            Self::NominalRateCurve { rate, .. }
+            | Self::EffectiveRateCurve { rate, .. }
+            | Self::ExponentialRateCurve { rate, .. } => rate,
+            Self::InterpolatedRateCurve(_) => unreachable!(),
+        }
+    }
+
+    /**
+    Shift all the rates of the RateCurve by a spread
+
+    - spread    = spread to be added to each rate of the curve
+     */
+    pub fn shift(&self, spread: f64) -> RateCurve {
+        let sh = |rate: &Vec<f64>| rate.iter().map(|x| x + spread).collect();
+        match self {
+            Self::NominalRateCurve { rate, freq } => Self::NominalRateCurve {
+                rate: sh(rate),
+                freq: *freq,
+            },
+            Self::EffectiveRateCurve { rate, freq } => Self::EffectiveRateCurve {
+                rate: sh(rate),
+                freq: *freq,
+            },
+            Self::ExponentialRateCurve { rate, freq } => Self::ExponentialRateCurve {
+                rate: sh(rate),
+                freq: *freq,
+            },
+            Self::InterpolatedRateCurve(ic) => ic.curve().shift(spread).interpolated_as(self),
         }
     }
 
@@ -95,6 +182,7 @@ impl RateCurve {
                 freq: *freq,
             },
             Self::NominalRateCurve { rate: _, freq: _ } => (*self).clone(),
+            Self::InterpolatedRateCurve(ic) => ic.curve().to_nominal().interpolated_as(self),
         }
     }
 
@@ -115,6 +203,7 @@ impl RateCurve {
                 freq: *freq,
             },
             Self::EffectiveRateCurve { rate: _, freq: _ } => (*self).clone(),
+            Self::InterpolatedRateCurve(ic) => ic.curve().to_effective().interpolated_as(self),
         }
     }
 
@@ -135,18 +224,154 @@ impl RateCurve {
                 freq: *freq,
             },
             Self::ExponentialRateCurve { rate: _, freq: _ } => (*self).clone(),
+            Self::InterpolatedRateCurve(ic) => ic.curve().to_exponential().interpolated_as(self),
         }
     }
+
+    /**
+    Convert the RateCurve to the same type of rates as another RateCurve
+
+    - like  = RateCurve whose type of rates is sought
+     */
+    pub fn to_type_of(&self, like: &RateCurve) -> RateCurve {
+        match like.pillars() {
+            Self::NominalRateCurve { .. } => self.to_nominal(),
+            Self::EffectiveRateCurve { .. } => self.to_effective(),
+            _ => self.to_exponential(),
+        }
+    }
+
+    /**
+    Nominal RateCurve sampled from continuously compounded zero rates at a regular grid
+    i / freq, so that a curve given as a function may be used by the functions taking a
+    RateCurve.
+
+    - freq  = frequency of the RateCurve
+    - t_max = time till which the RateCurve is needed
+    - zero  = continuously compounded zero rate at time t
+     */
+    pub fn from_zero_fn(freq: f64, t_max: f64, zero: impl Fn(f64) -> f64) -> RateCurve {
+        RateCurve::ExponentialRateCurve {
+            rate: (1..=(t_max * freq).round() as usize)
+                .map(|i| zero(i as f64 / freq))
+                .collect(),
+            freq,
+        }
+        .to_nominal()
+    }
+
+    /** Frequency at which the rates are given per period */
+    pub fn freq(&self) -> f64 {
+        match self {
+            Self::NominalRateCurve { freq, .. }
+            | Self::EffectiveRateCurve { freq, .. }
+            | Self::ExponentialRateCurve { freq, .. } => *freq,
+            Self::InterpolatedRateCurve(ic) => ic.curve().freq(),
+        }
+    }
+
+    /** Compounding of the rates of the RateCurve */
+    pub fn compounding(&self) -> Compounding {
+        match self.pillars() {
+            Self::NominalRateCurve { freq, .. } => Compounding::Nominal(*freq),
+            Self::EffectiveRateCurve { .. } => Compounding::Effective,
+            _ => Compounding::Exponential,
+        }
+    }
+
+    /**
+    Format the RateCurve as CSV with a row of (tenor, rate) for each point i / freq. The
+    header of the rate column gives the type of rates as nominal, effective or exponential.
+    The interpolation of the curve, if any, is not saved.
+     */
+    pub fn format_csv(&self) -> String {
+        let kind = match self.pillars() {
+            Self::NominalRateCurve { .. } => "nominal",
+            Self::EffectiveRateCurve { .. } => "effective",
+            _ => "exponential",
+        };
+        let rate = self.rates();
+        let freq = self.freq();
+        rate.iter()
+            .enumerate()
+            .fold(format!("tenor,{kind}\n"), |s, (i, r)| {
+                s + &format!("{},{}\n", (i + 1) as f64 / freq, r)
+            })
+    }
+
+    /**
+    Parse a RateCurve from CSV of (tenor, rate) given on a regular grid i / freq, with an
+    optional header. A rate column not named effective or exponential is taken as nominal.
+    Returns an error if a line is malformed, there are no rates or the grid is irregular.
+
+    - text  = CSV text
+     */
+    pub fn parse_csv(text: &str) -> Result<RateCurve, FileError> {
+        let mut kind = "nominal".to_string();
+        let mut points: Vec<(f64, f64)> = vec![];
+        let lines = text.lines().map(|x| x.trim()).filter(|x| !x.is_empty());
+        for (i, line) in lines.enumerate() {
+            let err = || FileError::Format(format!("invalid line {}: {line}", i + 1));
+            let (t, r) = line.split_once(',').ok_or_else(err)?;
+            match (t.trim().parse::<f64>(), r.trim().parse::<f64>()) {
+                (Ok(t), Ok(r)) => points.push((t, r)),
+                _ if i == 0 => kind = r.trim().to_lowercase(),
+                _ => return Err(err()),
+            }
+        }
+        let t0 = points
+            .first()
+            .ok_or_else(|| FileError::Format("no rates".to_string()))?
+            .0;
+        let freq = (1.0 / t0).round();
+        if let Some((i, p)) = points
+            .iter()
+            .enumerate()
+            .find(|(i, p)| !freq.is_finite() || (p.0 * freq - (i + 1) as f64).abs() > 1e-6)
+        {
+            return Err(FileError::Format(format!(
+                "tenor {} of row {} is not on the regular grid of freq {freq}",
+                p.0,
+                i + 1
+            )));
+        }
+        let rate = points.iter().map(|p| p.1).collect();
+        Ok(match kind.as_str() {
+            "effective" => Self::EffectiveRateCurve { rate, freq },
+            "exponential" => Self::ExponentialRateCurve { rate, freq },
+            _ => Self::NominalRateCurve { rate, freq },
+        })
+    }
+
+    /**
+    Save the RateCurve as CSV file of (tenor, rate)
+
+    - file  = path of the file
+     */
+    pub fn to_csv(&self, file: &str) -> Result<(), FileError> {
+        Ok(std::fs::write(file, self.format_csv())?)
+    }
+
+    /**
+    Load a RateCurve from CSV file of (tenor, rate)
+
+    - file  = path of the file
+     */
+    pub fn from_csv(file: &str) -> Result<RateCurve, FileError> {
+        Self::parse_csv(&std::fs::read_to_string(file)?)
+    }
 }
 
 /**
 The Rates enum defines different types of Rates represemted in RateCurves
 
-- SpotRates
-- ParRates
-- ForwardRates
+- SpotRates     => Zero rates for each time i / freq
+- ParRates      => Yields of the par bonds paying coupon freq times a period and maturing at i / freq
+- ForwardRates  => One period forward rates for the period ((i - 1) / freq, i / freq)
+
+The rates may be of any type of RateCurve (Nominal, Effective, Exponential)
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Rates {
     SpotRates { rate: RateCurve },
     ParRates { rate: RateCurve },
@@ -154,17 +379,29 @@ pub enum Rates {
 }
 
 impl Rates {
-    /** Change ParRates to SpotRates */
+    /** The RateCurve of the Rates */
+    pub fn curve(&self) -> &RateCurve {
+        match self {
+            Self::SpotRates { rate } | Self::ParRates { rate } | Self::ForwardRates { rate } => {
+                rate
+            }
+        }
+    }
+
+    /**
+    Change ParRates or ForwardRates to SpotRates. The SpotRates are given as the same
+    type of rates (Nominal, Effective, Exponential) as the RateCurve.
+     */
     pub fn to_spot(&self) -> Rates {
-        match &self {
-            &Self::ParRates { rate } => {
-                let (rt, fq) = match &rate {
-                    &RateCurve::NominalRateCurve { rate, freq } => (rate, freq),
-                    _ => unimplemented!(),
-                };
-                let n = rt.len();
+        let rc = self.curve();
+        let RateCurve::NominalRateCurve { rate: rt, freq: fq } = rc.pillars().to_nominal() else {
+            unreachable!()
+        };
+        let n = rt.len();
+        let y = match self {
+            Self::SpotRates { .. } => return self.clone(),
+            Self::ParRates { .. } => {
                 let mut y = vec![0.0; n];
-
                 y[0] = rt[0];
                 (1..n).for_each(|i| {
                     let xm = rt[i] / fq;
@@ -173,40 +410,91 @@ impl Rates {
                         .sum::<f64>();
                     y[i] = (((1.0 + xm) / (1.0 - sm)).powf(1.0 / ((i + 1) as f64)) - 1.0) * fq
                 });
-                Rates::SpotRates {
-                    rate: RateCurve::NominalRateCurve { rate: y, freq: *fq },
-                }
+                y
+            }
+            Self::ForwardRates { .. } => {
+                let mut g = 1.0;
+                rt.iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        g *= 1.0 + f / fq;
+                        (g.powf(1.0 / ((i + 1) as f64)) - 1.0) * fq
+                    })
+                    .collect()
             }
-            _ => unimplemented!(),
+        };
+        Rates::SpotRates {
+            rate: RateCurve::NominalRateCurve { rate: y, freq: fq }
+                .to_type_of(rc)
+                .interpolated_as(rc),
         }
     }
 
-    /** Change SpotRates to ParRates */
+    /**
+    Change SpotRates or ForwardRates to ParRates. The ParRates are given as the yield
+    of the par bonds in the same type of rates as the RateCurve.
+     */
     pub fn to_par(&self) -> Rates {
-        match &self {
-            &Self::SpotRates { rate } => {
-                let (rt, fq) = match &rate {
-                    &RateCurve::NominalRateCurve { rate, freq } => (rate, freq),
-                    _ => unimplemented!(),
-                };
-
-                Rates::ParRates {
-                    rate: RateCurve::NominalRateCurve {
-                        rate: {
-                            (0..rt.len())
-                                .map(|i| {
-                                    fq * (1.0 - 1.0 / (1.0 + rt[i] / fq).powf((i + 1) as f64))
-                                        / (0..=i)
-                                            .map(|k| 1.0 / (1.0 + rt[k] / fq).powf((k + 1) as f64))
-                                            .sum::<f64>()
-                                })
-                                .collect()
-                        },
-                        freq: *fq,
-                    },
-                }
+        if let Self::ParRates { .. } = self {
+            return self.clone();
+        }
+        let Self::SpotRates { rate } = self.to_spot() else {
+            unreachable!()
+        };
+        let RateCurve::NominalRateCurve { rate: rt, freq: fq } = rate.pillars().to_nominal() else {
+            unreachable!()
+        };
+
+        Rates::ParRates {
+            rate: RateCurve::NominalRateCurve {
+                rate: {
+                    (0..rt.len())
+                        .map(|i| {
+                            fq * (1.0 - 1.0 / (1.0 + rt[i] / fq).powf((i + 1) as f64))
+                                / (0..=i)
+                                    .map(|k| 1.0 / (1.0 + rt[k] / fq).powf((k + 1) as f64))
+                                    .sum::<f64>()
+                        })
+                        .collect()
+                },
+                freq: fq,
+            }
+            .to_type_of(&rate)
+            .interpolated_as(&rate),
+        }
+    }
+
+    /**
+    Change SpotRates or ParRates to ForwardRates, where the i-th rate is the forward
+    rate for the period (i / freq, (i + 1) / freq). The ForwardRates are given as the
+    same type of rates as the RateCurve.
+     */
+    pub fn to_forward(&self) -> Rates {
+        if let Self::ForwardRates { .. } = self {
+            return self.clone();
+        }
+        let Self::SpotRates { rate } = self.to_spot() else {
+            unreachable!()
+        };
+        let RateCurve::NominalRateCurve { rate: rt, freq: fq } = rate.pillars().to_nominal() else {
+            unreachable!()
+        };
+        let df = |i: usize| {
+            if i == 0 {
+                1.0
+            } else {
+                (1.0 + rt[i - 1] / fq).powf(-(i as f64))
+            }
+        };
+        Rates::ForwardRates {
+            rate: RateCurve::NominalRateCurve {
+                rate: (1..=rt.len())
+                    .map(|i| (df(i - 1) / df(i) - 1.0) * fq)
+                    .collect(),
+                freq: fq,
             }
-            _ => unimplemented!(),
+            .to_type_of(&rate)
+            .interpolated_as(&rate),
         }
     }
 
@@ -223,18 +511,30 @@ impl Rates {
     }
 
     /**
-    Estimate the forward rate for a given forward period of a given tenor
+    Estimate the forward rate for a given forward period of a given tenor. The rate is
+    given as the same type of rates as the RateCurve.
 
     - forward_period    = forward period start point
     - tenor             = tenor of the forward period
      */
     pub fn forward_rate(&self, forward_period: f64, tenor: f64) -> f64 {
-        let &Self::SpotRates { rate } = &self else {
-            unimplemented!()
+        let Self::SpotRates { rate } = self else {
+            return self.to_spot().forward_rate(forward_period, tenor);
         };
         let ft = forward_period + tenor;
+        if let RateCurve::InterpolatedRateCurve(_) = rate {
+            let g = rate.pv(1.0, forward_period) / rate.pv(1.0, ft);
+            return rate.compounding().rate(g, tenor);
+        }
         let RateCurve::NominalRateCurve { freq: f, .. } = rate else {
-            unimplemented!()
+            let fr = Rates::SpotRates {
+                rate: rate.to_nominal(),
+            }
+            .forward_rate(forward_period, tenor);
+            return match rate {
+                RateCurve::EffectiveRateCurve { freq, .. } => crate::nom_eff_rate(fr, *freq),
+                _ => crate::nom_exp_rate(fr, rate.freq()),
+            };
         };
         (((1.0 + rate.rate_estim(ft) / f).powf(ft * f)
             / (1.0 + rate.rate_estim(forward_period) / f).powf(forward_period * f))
@@ -367,4 +667,190 @@ mod rate_fn {
         };
         assert_eq!(rt.forward_rate(3.0, 1.0), 0.057782903318259304);
     }
+
+    #[test]
+    fn rate_curve_extrapolation() {
+        assert_eq!(
+            NominalRateCurve {
+                rate: vec![0.05, 0.06, 0.07, 0.08],
+                freq: 2.0
+            }
+            .rate_estim(2.7),
+            0.08
+        );
+    }
+
+    #[test]
+    fn rate_conversions() {
+        let rates = |rc: &RateCurve| rc.rates().to_vec();
+        let same = |a: &Rates, b: &Rates| {
+            let (x, y) = (rates(a.curve()), rates(b.curve()));
+            x.len() == y.len() && x.iter().zip(&y).all(|(p, q)| approx(*p, *q))
+        };
+
+        // Round trips over pseudo-random upward, downward and humped curves
+        let mut rng = crate::Generator::new(42);
+        let mut rnd = || rng.uniform();
+        for _ in 0..50 {
+            let (n, freq) = (
+                1 + (rnd() * 20.0) as usize,
+                [1.0, 2.0, 4.0][(rnd() * 3.0) as usize],
+            );
+            let (a, b, c) = (
+                0.01 + 0.06 * rnd(),
+                0.04 * rnd() - 0.02,
+                0.002 * rnd() - 0.001,
+            );
+            let rate: Vec<f64> = (1..=n)
+                .map(|i| a + b * (i as f64 / n as f64) + c * i as f64)
+                .collect();
+            for rc in [
+                NominalRateCurve {
+                    rate: rate.clone(),
+                    freq,
+                },
+                EffectiveRateCurve {
+                    rate: rate.clone(),
+                    freq,
+                },
+                ExponentialRateCurve {
+                    rate: rate.clone(),
+                    freq,
+                },
+            ] {
+                let sr = Rates::SpotRates { rate: rc.clone() };
+                let (pr, fr) = (sr.to_par(), sr.to_forward());
+                assert!(same(&pr.to_spot(), &sr));
+                assert!(same(&fr.to_spot(), &sr));
+                assert!(same(&pr.to_forward(), &fr));
+                assert!(same(&fr.to_par(), &pr));
+                assert!(matches!(
+                    (rc.clone(), pr.curve(), fr.curve()),
+                    (
+                        NominalRateCurve { .. },
+                        NominalRateCurve { .. },
+                        NominalRateCurve { .. }
+                    ) | (
+                        EffectiveRateCurve { .. },
+                        EffectiveRateCurve { .. },
+                        EffectiveRateCurve { .. }
+                    ) | (
+                        ExponentialRateCurve { .. },
+                        ExponentialRateCurve { .. },
+                        ExponentialRateCurve { .. }
+                    )
+                ));
+
+                let k = n / 2;
+                let (t0, tn) = (k as f64 / freq, 1.0 / freq);
+                let fw = rates(fr.curve())[k];
+                assert!(approx(sr.forward_rate(t0, tn), fw));
+                assert!(approx(pr.forward_rate(t0, tn), fw));
+                assert!(approx(fr.forward_rate(t0, tn), fw));
+            }
+        }
+
+        let ex = Rates::SpotRates {
+            rate: ExponentialRateCurve {
+                rate: vec![0.03, 0.04],
+                freq: 1.0,
+            },
+        };
+        assert!(approx(ex.forward_rate(1.0, 1.0), 0.05));
+    }
+
+    #[test]
+    fn file_formats() {
+        use crate::FileFormats;
+        use crate::derivatives::forwards::XForward;
+        use crate::fixedincomes::bonds::{CouponBond, FloatingRateNotes};
+        use chrono::naive::NaiveDate as NDt;
+
+        let dir = std::env::temp_dir();
+        let file = |x: &str| {
+            let name = format!("financelib_{}_{x}", std::process::id());
+            dir.join(name).to_str().unwrap().to_string()
+        };
+        let rc = EffectiveRateCurve {
+            rate: vec![0.0475, 0.05, 0.0525, 0.055],
+            freq: 2.0,
+        };
+        assert_eq!(
+            rc.format_csv(),
+            "tenor,effective\n0.5,0.0475\n1,0.05\n1.5,0.0525\n2,0.055\n"
+        );
+        rc.to_csv(&file("rc.csv")).unwrap();
+        let rx = RateCurve::from_csv(&file("rc.csv")).unwrap();
+        assert_eq!(format!("{:?}", rx), format!("{:?}", rc));
+        assert!(matches!(
+            RateCurve::parse_csv("0.25,0.02\n0.5,0.021\n"),
+            Ok(NominalRateCurve { freq: 4.0, .. })
+        ));
+        assert!(RateCurve::parse_csv("abc\n").is_err());
+        assert!(RateCurve::parse_csv("tenor,nominal\n").is_err());
+        assert!(RateCurve::parse_csv("0.25,0.02\n1.0,0.03\n").is_err());
+        assert!(RateCurve::parse_csv("0.5,0.02\n1.0,x\n").is_err());
+        assert!(matches!(
+            RateCurve::from_csv(&file("missing.csv")),
+            Err(crate::FileError::Io(_))
+        ));
+
+        let rs = Rates::ParRates { rate: rc.clone() };
+        rs.to_ron_file(&file("rates.ron")).unwrap();
+        let rx = Rates::from_ron_file(&file("rates.ron")).unwrap();
+        assert_eq!(format!("{:?}", rx), format!("{:?}", rs));
+        rs.to_json_file(&file("rates.json")).unwrap();
+        let rx = Rates::from_json_file(&file("rates.json")).unwrap();
+        assert!(matches!(
+            Rates::from_ron_file(&file("rates.json")),
+            Err(crate::FileError::Format(_))
+        ));
+        assert_eq!(format!("{:?}", rx), format!("{:?}", rs));
+
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 3.0,
+        };
+        let cx: CouponBond = ron::from_str(&ron::to_string(&cb).unwrap()).unwrap();
+        assert_eq!(cx.price(0.04), cb.price(0.04));
+        let frn = FloatingRateNotes {
+            cap: Some(0.07),
+            ..FloatingRateNotes::new(100.0, 0.005, 4.0, 5.0)
+        };
+        let js = serde_json::to_string(&frn).unwrap();
+        assert_eq!(
+            format!(
+                "{:?}",
+                serde_json::from_str::<FloatingRateNotes>(&js).unwrap()
+            ),
+            format!("{:?}", frn)
+        );
+        let old: FloatingRateNotes =
+            serde_json::from_str(r#"{"par":100.0,"quoted_margin":0.005,"freq":4.0,"t_life":5.0}"#)
+                .unwrap();
+        assert_eq!(old.cap, None);
+        let xf = XForward {
+            rf: 0.05,
+            dt_begin: NDt::from_ymd_opt(2024, 1, 15).unwrap(),
+            dt_expiry: NDt::from_ymd_opt(2024, 7, 15).unwrap(),
+            fwd_expiry: 102.0,
+            benefit: 0.0,
+            basis: crate::DayCountConvention::ACT360,
+        };
+        xf.to_json_file(&file("xfwd.json")).unwrap();
+        let xx = XForward::from_json_file(&file("xfwd.json")).unwrap();
+        assert_eq!(xx.dt_expiry, xf.dt_expiry);
+        assert_eq!(xx.basis, xf.basis);
+        let old: XForward = serde_json::from_str(
+            r#"{"rf":0.05,"dt_begin":"2024-01-15","dt_expiry":"2024-07-15","fwd_expiry":102.0,"benefit":0.0}"#,
+        )
+        .unwrap();
+        assert_eq!(old.basis, crate::DayCountConvention::US30360);
+
+        ["rc.csv", "rates.ron", "rates.json", "xfwd.json"]
+            .iter()
+            .for_each(|x| std::fs::remove_file(file(x)).unwrap());
+    }
 }
@@ -0,0 +1,364 @@
+/*!
+Implement Curve scenario modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::scenarios <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the shocks to yield curves like parallel shifts, twists,
+butterflies, key rate bumps and the six IRRBB shocks, along with the revaluation of a
+set of instruments under a set of scenarios to report the P&L.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::CouponBond;
+use super::portfolios::BondPortfolio;
+use super::rates::{RateCurve, Rates};
+use serde::{Deserialize, Serialize};
+
+/**
+Enum defining the shocks to a yield curve, given as the shift in rate at each time
+
+- Parallel  => Same shift at all times
+- Twist     => shift = slope * (t - pivot), so a positive slope steepens and a negative slope flattens the curve
+- Butterfly => shift of wing at or beyond the short and long pivots and of belly at their midpoint, linear in between
+- KeyRate   => Triangular bump of shift at tenors[index], falling to zero at the neighbouring tenors and flat beyond the end tenors
+- ShortLong => shift = short * exp(-t / 4) + long * (1 - exp(-t / 4)), as used by the IRRBB shocks
+- Custom    => Piecewise linear shift given as vector of (time, shift), flat beyond the end points
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CurveShock {
+    Parallel(f64),
+    Twist {
+        pivot: f64,
+        slope: f64,
+    },
+    Butterfly {
+        short: f64,
+        long: f64,
+        wing: f64,
+        belly: f64,
+    },
+    KeyRate {
+        tenors: Vec<f64>,
+        index: usize,
+        shift: f64,
+    },
+    ShortLong {
+        short: f64,
+        long: f64,
+    },
+    Custom(Vec<(f64, f64)>),
+}
+
+/** Piecewise linear interpolation of (x, y) points, flat beyond the end points */
+fn piecewise(p: &[(f64, f64)], t: f64) -> f64 {
+    let n = p.len();
+    if t <= p[0].0 {
+        return p[0].1;
+    }
+    if t >= p[n - 1].0 {
+        return p[n - 1].1;
+    }
+    let i = p.iter().take_while(|x| x.0 < t).count();
+    let ((x0, y0), (x1, y1)) = (p[i - 1], p[i]);
+    y0 + (y1 - y0) * (t - x0) / (x1 - x0)
+}
+
+impl CurveShock {
+    /**
+    Shift in rate at a time
+
+    - t = time given as period
+     */
+    pub fn shift(&self, t: f64) -> f64 {
+        match self {
+            Self::Parallel(s) => *s,
+            Self::Twist { pivot, slope } => slope * (t - pivot),
+            Self::Butterfly {
+                short,
+                long,
+                wing,
+                belly,
+            } => piecewise(
+                &[
+                    (*short, *wing),
+                    ((short + long) / 2.0, *belly),
+                    (*long, *wing),
+                ],
+                t,
+            ),
+            Self::KeyRate {
+                tenors,
+                index,
+                shift,
+            } => {
+                let n = tenors.len();
+                let mut p = vec![(tenors[*index], *shift)];
+                if *index > 0 {
+                    p.insert(0, (tenors[index - 1], 0.0));
+                }
+                if *index + 1 < n {
+                    p.push((tenors[index + 1], 0.0));
+                }
+                piecewise(&p, t)
+            }
+            Self::ShortLong { short, long } => {
+                let d = (-t / 4.0).exp();
+                short * d + long * (1.0 - d)
+            }
+            Self::Custom(p) => piecewise(p, t),
+        }
+    }
+}
+
+/**
+Key rate shocks for each of the tenors
+
+- tenors    = Vector of key rate tenors in ascending order
+- shift     = Shift in rate at the key rate tenor
+ */
+pub fn key_rate_shocks(tenors: &[f64], shift: f64) -> Vec<CurveShock> {
+    (0..tenors.len())
+        .map(|index| CurveShock::KeyRate {
+            tenors: tenors.to_vec(),
+            index,
+            shift,
+        })
+        .collect()
+}
+
+/**
+Scenario : struct defining a named shock to a yield curve
+
+- name  = Name of the scenario
+- shock = Shock to the yield curve
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub shock: CurveShock,
+}
+
+/**
+Six interest rate shocks of the Basel IRRBB standard
+
+- parallel  = Size of the parallel shock
+- short     = Size of the short rate shock
+- long      = Size of the long rate shock
+ */
+pub fn irrbb_scenarios(parallel: f64, short: f64, long: f64) -> Vec<Scenario> {
+    let sc = |name: &str, shock| Scenario {
+        name: name.to_string(),
+        shock,
+    };
+    let sl = |short, long| CurveShock::ShortLong { short, long };
+    vec![
+        sc("Parallel up", CurveShock::Parallel(parallel)),
+        sc("Parallel down", CurveShock::Parallel(-parallel)),
+        sc("Steepener", sl(-0.65 * short, 0.9 * long)),
+        sc("Flattener", sl(0.8 * short, -0.6 * long)),
+        sc("Short rate up", sl(short, 0.0)),
+        sc("Short rate down", sl(-short, 0.0)),
+    ]
+}
+
+impl RateCurve {
+    /**
+    RateCurve with the rate at each time i / freq shifted by a shock
+
+    - shock = Shock to the curve
+     */
+    pub fn shock(&self, shock: &CurveShock) -> RateCurve {
+        let freq = self.freq();
+        let sh = |rate: &Vec<f64>| -> Vec<f64> {
+            rate.iter()
+                .enumerate()
+                .map(|(i, r)| r + shock.shift((i + 1) as f64 / freq))
+                .collect()
+        };
+        match self {
+            Self::NominalRateCurve { rate, .. } => Self::NominalRateCurve {
+                rate: sh(rate),
+                freq,
+            },
+            Self::EffectiveRateCurve { rate, .. } => Self::EffectiveRateCurve {
+                rate: sh(rate),
+                freq,
+            },
+            Self::ExponentialRateCurve { rate, .. } => Self::ExponentialRateCurve {
+                rate: sh(rate),
+                freq,
+            },
+            Self::InterpolatedRateCurve(ic) => ic.curve().shock(shock).interpolated_as(self),
+        }
+    }
+}
+
+impl Rates {
+    /**
+    Rates with the RateCurve shifted by a shock
+
+    - shock = Shock to the curve
+     */
+    pub fn shock(&self, shock: &CurveShock) -> Rates {
+        match self {
+            Self::SpotRates { rate } => Self::SpotRates {
+                rate: rate.shock(shock),
+            },
+            Self::ParRates { rate } => Self::ParRates {
+                rate: rate.shock(shock),
+            },
+            Self::ForwardRates { rate } => Self::ForwardRates {
+                rate: rate.shock(shock),
+            },
+        }
+    }
+}
+
+/**
+Trait for instruments which may be revalued on a spot RateCurve
+ */
+pub trait Revaluation {
+    fn value(&self, rc: &RateCurve) -> f64;
+}
+
+impl Revaluation for CouponBond {
+    fn value(&self, rc: &RateCurve) -> f64 {
+        self.price_ratecurve(rc)
+    }
+}
+
+impl Revaluation for BondPortfolio {
+    fn value(&self, rc: &RateCurve) -> f64 {
+        self.market_value(rc)
+    }
+}
+
+impl<F: Fn(&RateCurve) -> f64> Revaluation for F {
+    fn value(&self, rc: &RateCurve) -> f64 {
+        self(rc)
+    }
+}
+
+/**
+P&L of a set of instruments under a scenario
+
+- name  = Name of the scenario
+- pnl   = P&L of each instrument
+- total = Total P&L
+ */
+#[derive(Debug, Clone)]
+pub struct ScenarioPnL {
+    pub name: String,
+    pub pnl: Vec<f64>,
+    pub total: f64,
+}
+
+/**
+Revalue a set of instruments under each scenario and report the P&L against the base
+spot RateCurve
+
+- rc            = Base spot RateCurve
+- scenarios     = Scenarios to be evaluated
+- instruments   = Instruments to be revalued, given as vector of (instrument, quantity)
+ */
+pub fn scenario_pnl(
+    rc: &RateCurve,
+    scenarios: &[Scenario],
+    instruments: &[(&dyn Revaluation, f64)],
+) -> Vec<ScenarioPnL> {
+    let base: Vec<f64> = instruments.iter().map(|(x, q)| q * x.value(rc)).collect();
+    scenarios
+        .iter()
+        .map(|sc| {
+            let rs = rc.shock(&sc.shock);
+            let pnl: Vec<f64> = instruments
+                .iter()
+                .zip(&base)
+                .map(|((x, q), b)| q * x.value(&rs) - b)
+                .collect();
+            ScenarioPnL {
+                name: sc.name.clone(),
+                total: pnl.iter().sum(),
+                pnl,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod scenarios_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn curve_scenarios() {
+        assert_eq!(CurveShock::Parallel(0.01).shift(7.0), 0.01);
+        assert!(approx(
+            CurveShock::Twist {
+                pivot: 5.0,
+                slope: 0.001
+            }
+            .shift(10.0),
+            0.005
+        ));
+        let bf = CurveShock::Butterfly {
+            short: 2.0,
+            long: 10.0,
+            wing: 0.0025,
+            belly: -0.0025,
+        };
+        assert_eq!(bf.shift(1.0), 0.0025);
+        assert!(approx(bf.shift(6.0), -0.0025));
+        assert!(approx(bf.shift(4.0), 0.0));
+        let kr = key_rate_shocks(&[1.0, 2.0, 5.0, 10.0], 0.0001);
+        assert!(approx(kr[2].shift(3.5), 0.00005));
+        assert_eq!(kr[0].shift(0.5), 0.0001);
+        assert_eq!(kr[3].shift(30.0), 0.0001);
+        assert!(approx(
+            (0..60)
+                .map(|i| i as f64 * 0.25)
+                .map(|t| kr.iter().map(|k| k.shift(t)).sum::<f64>())
+                .sum::<f64>(),
+            60.0 * 0.0001
+        ));
+
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+        let sc = irrbb_scenarios(0.02, 0.025, 0.01);
+        assert_eq!(sc.len(), 6);
+        let Rates::SpotRates { rate: rs } =
+            Rates::SpotRates { rate: rc.clone() }.shock(&sc[2].shock)
+        else {
+            panic!()
+        };
+        assert!(rs.rate_estim(0.5) < 0.02 && rs.rate_estim(5.0) > 0.0330);
+
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let bp = BondPortfolio {
+            positions: vec![(cb, 10.0)],
+        };
+        let fx = |rc: &RateCurve| rc.pv(100.0, 1.0);
+        let res = scenario_pnl(&rc, &sc, &[(&cb, 10.0), (&bp, -1.0), (&fx, 1.0)]);
+        assert!(approx(
+            res[0].pnl[0],
+            10.0 * (cb.price_ratecurve(&rc.shift(0.02)) - cb.price_ratecurve(&rc))
+        ));
+        assert!(res.iter().all(|x| approx(x.pnl[0] + x.pnl[1], 0.0)));
+        assert!(res[0].total < 0.0 && res[1].total > 0.0);
+        assert!(res[4].pnl[2] < 0.0);
+    }
+}
@@ -0,0 +1,655 @@
+/*!
+Implement Short rate model modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::shortrates <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the one factor short rate models of Vasicek, Cox-Ingersoll-Ross and
+Hull-White, with their closed form zero coupon bond prices, calibration to a RateCurve,
+simulation of the short rate paths and the prices of European options on bonds. The
+short rate is given as a continuously compounded rate.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::rates::RateCurve;
+use super::{CashFlowSchedule, CouponBond};
+use serde::{Deserialize, Serialize};
+
+/** Standard normal CDF */
+fn norm_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let erfc = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+    if x >= 0.0 {
+        1.0 - 0.5 * erfc
+    } else {
+        0.5 * erfc
+    }
+}
+
+/** Log of the Gamma function by the Lanczos approximation */
+fn ln_gamma(x: f64) -> f64 {
+    const C: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let ser = C
+        .iter()
+        .enumerate()
+        .fold(1.000000000190015, |s, (j, c)| s + c / (x + 1.0 + j as f64));
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
+
+/** Regularized lower incomplete Gamma function P(a, x) */
+fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let lg = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        while term.abs() > sum.abs() * 1e-15 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+        }
+        sum * lg.exp()
+    } else {
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            d = if d.abs() < tiny { 1.0 / tiny } else { 1.0 / d };
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            let del = d * c;
+            h *= del;
+            if (del - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        1.0 - lg.exp() * h
+    }
+}
+
+/**
+Noncentral Chi-square CDF as the Poisson mixture of the central Chi-square CDFs
+
+- x     = value
+- k     = degrees of freedom
+- lam   = noncentrality parameter
+ */
+fn ncx2_cdf(x: f64, k: f64, lam: f64) -> f64 {
+    let m = lam / 2.0;
+    let j0 = m.floor() as usize;
+    let w = |j: usize| (-m + j as f64 * m.ln() - ln_gamma(j as f64 + 1.0)).exp();
+    let term = |j: usize| {
+        if m > 0.0 {
+            w(j) * gamma_p(k / 2.0 + j as f64, x / 2.0)
+        } else if j == 0 {
+            gamma_p(k / 2.0, x / 2.0)
+        } else {
+            0.0
+        }
+    };
+    let mut sum = term(j0);
+    for j in (j0 + 1).. {
+        let t = term(j);
+        sum += t;
+        if w(j) < 1e-16 || m == 0.0 {
+            break;
+        }
+    }
+    for j in (0..j0).rev() {
+        sum += term(j);
+        if w(j) < 1e-16 {
+            break;
+        }
+    }
+    sum
+}
+
+/**
+Trait for one factor short rate models dr = drift(t, r) dt + diffusion(t, r) dW
+ */
+pub trait ShortRateModel {
+    /** Short rate at time 0 */
+    fn r0(&self) -> f64;
+
+    /** Drift of the short rate at time t */
+    fn drift(&self, t: f64, r: f64) -> f64;
+
+    /** Diffusion of the short rate at time t */
+    fn diffusion(&self, t: f64, r: f64) -> f64;
+
+    /**
+    Price at time t of a zero coupon bond of par 1 maturing at s, given the short rate r at t
+
+    - r = Short rate at t
+    - t = time given as period
+    - s = maturity of the bond
+     */
+    fn zcb(&self, r: f64, t: f64, s: f64) -> f64;
+
+    /**
+    Price at time 0 of a European option on a zero coupon bond of par 1
+
+    - expiry    = Expiry of the option
+    - maturity  = Maturity of the bond
+    - strike    = Strike price
+    - call      = true for a call and false for a put
+     */
+    fn zcb_option(&self, expiry: f64, maturity: f64, strike: f64, call: bool) -> f64;
+
+    /** Discount factor at time t */
+    fn df(&self, t: f64) -> f64 {
+        self.zcb(self.r0(), 0.0, t)
+    }
+
+    /** Continuously compounded zero rate at time t */
+    fn zero_rate(&self, t: f64) -> f64 {
+        -self.df(t).ln() / t
+    }
+
+    /**
+    Sample the model curve as Nominal spot rates at a regular grid i / freq
+
+    - freq  = frequency of the RateCurve
+    - t_max = time till which the RateCurve is needed
+     */
+    fn to_ratecurve(&self, freq: f64, t_max: f64) -> RateCurve {
+        RateCurve::from_zero_fn(freq, t_max, |t| self.zero_rate(t))
+    }
+
+    /**
+    Short rate at t + dt given the short rate r at t and a standard normal shock z, by
+    the Euler scheme unless the model has an exact transition
+
+    - t     = time given as period
+    - r     = Short rate at t
+    - dt    = time step
+    - z     = standard normal shock
+     */
+    fn step(&self, t: f64, r: f64, dt: f64, z: f64) -> f64 {
+        r + self.drift(t, r) * dt + self.diffusion(t, r) * dt.sqrt() * z
+    }
+
+    /**
+    Simulate paths of the short rate from a seeded random number generator
+
+    - t_max     = time till which the paths are simulated
+    - steps     = number of time steps
+    - n_paths   = number of paths
+    - seed      = seed of the random number generator
+     */
+    fn simulate(&self, t_max: f64, steps: usize, n_paths: usize, seed: u64) -> Vec<Vec<f64>> {
+        let dt = t_max / steps as f64;
+        let mut rng = crate::Generator::new(seed);
+        (0..n_paths)
+            .map(|_| {
+                let mut r = self.r0();
+                let mut path = vec![r];
+                for i in 0..steps {
+                    let t = i as f64 * dt;
+                    r = self.step(t, r, dt, rng.normal());
+                    path.push(r);
+                }
+                path
+            })
+            .collect()
+    }
+
+    /**
+    Price at time 0 of a European option on a Coupon bond by the Jamshidian
+    decomposition into options on zero coupon bonds
+
+    - cb        = CouponBond, with its cash flows measured from time 0
+    - expiry    = Expiry of the option
+    - strike    = Strike price, as the cash price of the bond at expiry
+    - call      = true for a call and false for a put
+
+    Returns None if no short rate at expiry gives the strike price
+     */
+    fn bond_option(&self, cb: &CouponBond, expiry: f64, strike: f64, call: bool) -> Option<f64> {
+        let cf: Vec<(f64, f64)> = cb
+            .cashflow_schedule()
+            .into_iter()
+            .filter(|&(t, _)| t > expiry)
+            .collect();
+        let rs = crate::newt_raph(
+            |r| {
+                cf.iter()
+                    .map(|&(t, c)| c * self.zcb(r, expiry, t))
+                    .sum::<f64>()
+                    - strike
+            },
+            self.r0(),
+            1e-12,
+        )?;
+        Some(
+            cf.iter()
+                .map(|&(t, c)| c * self.zcb_option(expiry, t, self.zcb(rs, expiry, t), call))
+                .sum(),
+        )
+    }
+}
+
+/**
+Vasicek : struct defining the Vasicek model dr = a (b - r) dt + sigma dW
+
+- a     = Speed of mean reversion
+- b     = Long term mean of the short rate
+- sigma = Volatility of the short rate
+- r0    = Short rate at time 0
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Vasicek {
+    pub a: f64,
+    pub b: f64,
+    pub sigma: f64,
+    pub r0: f64,
+}
+
+impl Vasicek {
+    /**
+    Least-squares fit of a, b and r0 to the zero rates of a RateCurve, keeping sigma
+
+    - rc    = spot RateCurve
+    - t_max = time till which the RateCurve is fitted
+    - x0    = Initial guess of the model
+
+    Returns None if the solver fails
+     */
+    pub fn calibrate(rc: &RateCurve, t_max: f64, x0: &Self) -> Option<Self> {
+        let freq = rc.freq();
+        let yields: Vec<(f64, f64)> = (1..=(t_max * freq).round() as usize)
+            .map(|i| i as f64 / freq)
+            .map(|t| (t, -rc.pv(1.0, t).ln() / t))
+            .collect();
+        let model = |x: &[f64]| Vasicek {
+            a: x[0].exp(),
+            b: x[1],
+            sigma: x0.sigma,
+            r0: x[2],
+        };
+        let x = crate::minimize(
+            |x| {
+                let m = model(x);
+                yields
+                    .iter()
+                    .map(|&(t, y)| (m.zero_rate(t) - y).powi(2))
+                    .sum()
+            },
+            &[x0.a.ln(), x0.b, x0.r0],
+            0.1,
+        )?;
+        Some(model(&x))
+    }
+}
+
+impl ShortRateModel for Vasicek {
+    fn r0(&self) -> f64 {
+        self.r0
+    }
+
+    fn drift(&self, _t: f64, r: f64) -> f64 {
+        self.a * (self.b - r)
+    }
+
+    fn diffusion(&self, _t: f64, _r: f64) -> f64 {
+        self.sigma
+    }
+
+    fn step(&self, _t: f64, r: f64, dt: f64, z: f64) -> f64 {
+        let e = (-self.a * dt).exp();
+        self.b + (r - self.b) * e + self.sigma * ((1.0 - e * e) / (2.0 * self.a)).sqrt() * z
+    }
+
+    fn zcb(&self, r: f64, t: f64, s: f64) -> f64 {
+        let (a, b, sg, tau) = (self.a, self.b, self.sigma, s - t);
+        let bb = (1.0 - (-a * tau).exp()) / a;
+        let la = (b - sg * sg / (2.0 * a * a)) * (bb - tau) - sg * sg * bb * bb / (4.0 * a);
+        (la - bb * r).exp()
+    }
+
+    fn zcb_option(&self, expiry: f64, maturity: f64, strike: f64, call: bool) -> f64 {
+        let (a, sg) = (self.a, self.sigma);
+        let sp = sg / a
+            * (1.0 - (-a * (maturity - expiry)).exp())
+            * ((1.0 - (-2.0 * a * expiry).exp()) / (2.0 * a)).sqrt();
+        gaussian_zcb_option(self.df(expiry), self.df(maturity), sp, strike, call)
+    }
+}
+
+/** Option on a zero coupon bond in a Gaussian model, given the bond price volatility sp */
+fn gaussian_zcb_option(pt: f64, ps: f64, sp: f64, strike: f64, call: bool) -> f64 {
+    let h = (ps / (strike * pt)).ln() / sp + sp / 2.0;
+    if call {
+        ps * norm_cdf(h) - strike * pt * norm_cdf(h - sp)
+    } else {
+        strike * pt * norm_cdf(sp - h) - ps * norm_cdf(-h)
+    }
+}
+
+/**
+CIR : struct defining the Cox-Ingersoll-Ross model dr = a (b - r) dt + sigma sqrt(r) dW
+
+- a     = Speed of mean reversion
+- b     = Long term mean of the short rate
+- sigma = Volatility factor of the short rate
+- r0    = Short rate at time 0
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CIR {
+    pub a: f64,
+    pub b: f64,
+    pub sigma: f64,
+    pub r0: f64,
+}
+
+impl CIR {
+    /** Functions A and B of the bond price P = A exp(-B r) for time to maturity tau */
+    fn ab(&self, tau: f64) -> (f64, f64) {
+        let (a, b, sg) = (self.a, self.b, self.sigma);
+        let h = (a * a + 2.0 * sg * sg).sqrt();
+        let e = (h * tau).exp() - 1.0;
+        let den = (h + a) * e + 2.0 * h;
+        let aa = (2.0 * h * ((a + h) * tau / 2.0).exp() / den).powf(2.0 * a * b / (sg * sg));
+        (aa, 2.0 * e / den)
+    }
+
+    /**
+    Least-squares fit of a, b and r0 to the zero rates of a RateCurve, keeping sigma
+
+    - rc    = spot RateCurve
+    - t_max = time till which the RateCurve is fitted
+    - x0    = Initial guess of the model
+
+    Returns None if the solver fails
+     */
+    pub fn calibrate(rc: &RateCurve, t_max: f64, x0: &Self) -> Option<Self> {
+        let freq = rc.freq();
+        let yields: Vec<(f64, f64)> = (1..=(t_max * freq).round() as usize)
+            .map(|i| i as f64 / freq)
+            .map(|t| (t, -rc.pv(1.0, t).ln() / t))
+            .collect();
+        let model = |x: &[f64]| CIR {
+            a: x[0].exp(),
+            b: x[1].exp(),
+            sigma: x0.sigma,
+            r0: x[2].exp(),
+        };
+        let x = crate::minimize(
+            |x| {
+                let m = model(x);
+                yields
+                    .iter()
+                    .map(|&(t, y)| (m.zero_rate(t) - y).powi(2))
+                    .sum()
+            },
+            &[x0.a.ln(), x0.b.ln(), x0.r0.ln()],
+            0.1,
+        )?;
+        Some(model(&x))
+    }
+}
+
+impl ShortRateModel for CIR {
+    fn r0(&self) -> f64 {
+        self.r0
+    }
+
+    fn drift(&self, _t: f64, r: f64) -> f64 {
+        self.a * (self.b - r.max(0.0))
+    }
+
+    fn diffusion(&self, _t: f64, r: f64) -> f64 {
+        self.sigma * r.max(0.0).sqrt()
+    }
+
+    fn zcb(&self, r: f64, t: f64, s: f64) -> f64 {
+        let (aa, bb) = self.ab(s - t);
+        aa * (-bb * r).exp()
+    }
+
+    fn zcb_option(&self, expiry: f64, maturity: f64, strike: f64, call: bool) -> f64 {
+        let (a, b, sg) = (self.a, self.b, self.sigma);
+        let h = (a * a + 2.0 * sg * sg).sqrt();
+        let rho = 2.0 * h / (sg * sg * ((h * expiry).exp() - 1.0));
+        let psi = (a + h) / (sg * sg);
+        let (aa, bb) = self.ab(maturity - expiry);
+        let rb = (aa / strike).ln() / bb;
+        let k = 4.0 * a * b / (sg * sg);
+        let nc = 2.0 * rho * rho * self.r0 * (h * expiry).exp();
+        let (pt, ps) = (self.df(expiry), self.df(maturity));
+        let c = ps * ncx2_cdf(2.0 * rb * (rho + psi + bb), k, nc / (rho + psi + bb))
+            - strike * pt * ncx2_cdf(2.0 * rb * (rho + psi), k, nc / (rho + psi));
+        if call { c } else { c - ps + strike * pt }
+    }
+}
+
+/**
+HullWhite : struct defining the Hull-White model dr = (theta(t) - a r) dt + sigma dW,
+where theta(t) is set to fit the initial RateCurve exactly
+
+- a     = Speed of mean reversion
+- sigma = Volatility of the short rate
+- curve = Initial spot RateCurve
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HullWhite {
+    pub a: f64,
+    pub sigma: f64,
+    pub curve: RateCurve,
+}
+
+impl HullWhite {
+    /**
+    New Hull-White model calibrated to a spot RateCurve
+
+    - a     = Speed of mean reversion
+    - sigma = Volatility of the short rate
+    - rc    = Initial spot RateCurve
+     */
+    pub fn calibrate(a: f64, sigma: f64, rc: &RateCurve) -> Self {
+        HullWhite {
+            a,
+            sigma,
+            curve: rc.clone(),
+        }
+    }
+
+    /** Instantaneous forward rate of the initial curve at time t */
+    pub fn forward_rate(&self, t: f64) -> f64 {
+        let dt = 1e-4;
+        let t0 = (t - dt).max(0.0);
+        (self.curve.pv(1.0, t0) / self.curve.pv(1.0, t + dt)).ln() / (t + dt - t0)
+    }
+
+    /** theta(t) which fits the model to the initial curve */
+    pub fn theta(&self, t: f64) -> f64 {
+        let dt = 1e-4;
+        let t0 = (t - dt).max(0.0);
+        let df = (self.forward_rate(t + dt) - self.forward_rate(t0)) / (t + dt - t0);
+        df + self.a * self.forward_rate(t)
+            + self.sigma * self.sigma / (2.0 * self.a) * (1.0 - (-2.0 * self.a * t).exp())
+    }
+
+    /** Mean of the short rate at time t, about which r - alpha(t) is a Vasicek process of mean zero */
+    fn alpha(&self, t: f64) -> f64 {
+        self.forward_rate(t) + (self.sigma * self.b(t)).powi(2) / 2.0
+    }
+
+    /** Function B of the bond price P = A exp(-B r) for time to maturity tau */
+    fn b(&self, tau: f64) -> f64 {
+        (1.0 - (-self.a * tau).exp()) / self.a
+    }
+}
+
+impl ShortRateModel for HullWhite {
+    fn r0(&self) -> f64 {
+        self.forward_rate(0.0)
+    }
+
+    fn drift(&self, t: f64, r: f64) -> f64 {
+        self.theta(t) - self.a * r
+    }
+
+    fn diffusion(&self, _t: f64, _r: f64) -> f64 {
+        self.sigma
+    }
+
+    fn step(&self, t: f64, r: f64, dt: f64, z: f64) -> f64 {
+        let e = (-self.a * dt).exp();
+        self.alpha(t + dt)
+            + (r - self.alpha(t)) * e
+            + self.sigma * ((1.0 - e * e) / (2.0 * self.a)).sqrt() * z
+    }
+
+    fn zcb(&self, r: f64, t: f64, s: f64) -> f64 {
+        let bb = self.b(s - t);
+        let la = (self.curve.pv(1.0, s) / self.curve.pv(1.0, t)).ln() + bb * self.forward_rate(t)
+            - self.sigma * self.sigma / (4.0 * self.a)
+                * (1.0 - (-2.0 * self.a * t).exp())
+                * bb
+                * bb;
+        (la - bb * r).exp()
+    }
+
+    fn df(&self, t: f64) -> f64 {
+        self.curve.pv(1.0, t)
+    }
+
+    fn zcb_option(&self, expiry: f64, maturity: f64, strike: f64, call: bool) -> f64 {
+        let a = self.a;
+        let sp = self.sigma
+            * self.b(maturity - expiry)
+            * ((1.0 - (-2.0 * a * expiry).exp()) / (2.0 * a)).sqrt();
+        gaussian_zcb_option(self.df(expiry), self.df(maturity), sp, strike, call)
+    }
+}
+
+#[cfg(test)]
+mod shortrates_fn {
+    use super::*;
+    use crate::approx;
+
+    /** Monte Carlo price of an option on a zero coupon bond from the simulated paths */
+    fn mc_zcb_option<M: ShortRateModel>(m: &M, expiry: f64, maturity: f64, strike: f64) -> f64 {
+        let steps = 200;
+        let dt = expiry / steps as f64;
+        let paths = m.simulate(expiry, steps, 20000, 42);
+        paths
+            .iter()
+            .map(|p| {
+                let ir: f64 = p.windows(2).map(|w| (w[0] + w[1]) / 2.0 * dt).sum();
+                (-ir).exp() * (m.zcb(p[steps], expiry, maturity) - strike).max(0.0)
+            })
+            .sum::<f64>()
+            / paths.len() as f64
+    }
+
+    #[test]
+    fn short_rate_models() {
+        let vs = Vasicek {
+            a: 0.3,
+            b: 0.05,
+            sigma: 0.01,
+            r0: 0.03,
+        };
+        assert!(approx(vs.zcb(0.03, 2.0, 2.0), 1.0));
+        assert!((vs.zero_rate(2000.0) - (0.05 - 0.01f64.powi(2) / (2.0 * 0.09))).abs() < 1e-4);
+        let (k, c, p) = (
+            0.93,
+            vs.zcb_option(1.0, 3.0, 0.93, true),
+            vs.zcb_option(1.0, 3.0, 0.93, false),
+        );
+        assert!(approx(c - p, vs.df(3.0) - k * vs.df(1.0)));
+        assert!((c - mc_zcb_option(&vs, 1.0, 3.0, k)).abs() < 0.02 * c);
+
+        let vf = Vasicek::calibrate(
+            &vs.to_ratecurve(2.0, 10.0),
+            10.0,
+            &Vasicek {
+                a: 0.2,
+                b: 0.04,
+                sigma: 0.01,
+                r0: 0.02,
+            },
+        )
+        .unwrap();
+        assert!((vf.a - 0.3).abs() < 1e-3 && (vf.b - 0.05).abs() < 1e-4);
+
+        let cir = CIR {
+            a: 0.4,
+            b: 0.05,
+            sigma: 0.08,
+            r0: 0.03,
+        };
+        assert!(approx(cir.zcb(0.03, 1.0, 1.0), 1.0));
+        let (k, c) = (0.92, cir.zcb_option(1.0, 3.0, 0.92, true));
+        assert!(approx(
+            c - cir.zcb_option(1.0, 3.0, k, false),
+            cir.df(3.0) - k * cir.df(1.0)
+        ));
+        assert!((c - mc_zcb_option(&cir, 1.0, 3.0, k)).abs() < 0.02 * c);
+        let cf =
+            CIR::calibrate(&cir.to_ratecurve(2.0, 10.0), 10.0, &CIR { r0: 0.02, ..cir }).unwrap();
+        assert!((cf.r0 - 0.03).abs() < 1e-4);
+
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+        let hw = HullWhite::calibrate(0.1, 0.01, &rc);
+        assert!(approx(hw.zcb(hw.r0(), 0.0, 3.5), rc.pv(1.0, 3.5)));
+        let (k, c) = (0.94, hw.zcb_option(1.0, 3.0, 0.94, true));
+        assert!((c - mc_zcb_option(&hw, 1.0, 3.0, k)).abs() < 0.02 * c);
+
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.04,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let fwd: f64 = cb
+            .cashflow_schedule()
+            .iter()
+            .filter(|x| x.0 > 2.0)
+            .map(|&(t, c)| c * hw.df(t))
+            .sum();
+        let (c, p) = (
+            hw.bond_option(&cb, 2.0, 100.0, true).unwrap(),
+            hw.bond_option(&cb, 2.0, 100.0, false).unwrap(),
+        );
+        assert!(approx(c - p, fwd - 100.0 * hw.df(2.0)));
+        assert!(c > 0.0 && p > 0.0);
+        assert!(hw.bond_option(&cb, 2.0, -100.0, true).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+/*!
+Implement Bond Spreads modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::spreads <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the spread measures of a Coupon bond given its market price,
+like G-spread, I-spread, Z-spread and Asset swap spreads.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::CouponBond;
+use super::rates::RateCurve;
+
+/**
+Spread measures of a Coupon bond at a market price
+
+- g_spread      = YTM - Government bond yield interpolated at maturity
+- i_spread      = YTM - Swap rate interpolated at maturity
+- z_spread      = Spread over the spot curve which reprices the bond
+- asw_par       = Par-par asset swap spread
+- asw_market    = Market value asset swap spread
+ */
+#[derive(Debug, Copy, Clone)]
+pub struct BondSpreads {
+    pub g_spread: f64,
+    pub i_spread: f64,
+    pub z_spread: f64,
+    pub asw_par: f64,
+    pub asw_market: f64,
+}
+
+impl CouponBond {
+    /**
+    G-spread of the bond over the Government bond yields
+
+    - price = Price of Coupon bond
+    - govt  = Government bond yields given as Nominal RateCurve
+     */
+    pub fn g_spread(&self, price: f64, govt: &RateCurve) -> f64 {
+        self.ytm(price) - govt.rate_estim(self.t_life)
+    }
+
+    /**
+    I-spread of the bond over the Swap rates
+
+    - price = Price of Coupon bond
+    - swap  = Par swap rates given as Nominal RateCurve
+     */
+    pub fn i_spread(&self, price: f64, swap: &RateCurve) -> f64 {
+        self.ytm(price) - swap.rate_estim(self.t_life)
+    }
+
+    /**
+    Price of Coupon bond given a spot RateCurve and a Z-spread over it
+
+    - rc    = Spot rates given as RateCurve
+    - z     = Z-spread added to each rate of the RateCurve
+     */
+    pub fn price_zspread(&self, rc: &RateCurve, z: f64) -> f64 {
+        self.price_ratecurve(&rc.shift(z))
+    }
+
+    /**
+    Z-spread of the bond over a spot RateCurve
+
+    - price = Price of Coupon bond
+    - rc    = Spot rates given as RateCurve
+
+    Returns None if no spread reprices the bond
+     */
+    pub fn z_spread(&self, price: f64, rc: &RateCurve) -> Option<f64> {
+        crate::newt_raph(|z| self.price_zspread(rc, z) - price, 0.005, 1e-6)
+    }
+
+    /**
+    PV of 1 unit of spread paid on the par value at each coupon date
+    discounted on the spot RateCurve
+     */
+    fn spread_annuity(&self, rc: &RateCurve) -> f64 {
+        (1..=((self.freq * self.t_life) as usize))
+            .map(|i| rc.pv(self.par / self.freq, i as f64 / self.freq))
+            .sum()
+    }
+
+    /**
+    Par-par asset swap spread, i.e. the spread over the floating leg on par value
+    which compensates for buying the bond at par.
+
+    - price = Price of Coupon bond
+    - rc    = Swap spot rates given as RateCurve
+     */
+    pub fn asw_par_par(&self, price: f64, rc: &RateCurve) -> f64 {
+        (self.price_ratecurve(rc) - price) / self.spread_annuity(rc)
+    }
+
+    /**
+    Market value asset swap spread, i.e. the spread over the floating leg on the
+    market value of the bond.
+
+    - price = Price of Coupon bond
+    - rc    = Swap spot rates given as RateCurve
+     */
+    pub fn asw_market_value(&self, price: f64, rc: &RateCurve) -> f64 {
+        self.asw_par_par(price, rc) * self.par / price
+    }
+
+    /**
+    All spread measures of the bond at a market price
+
+    - price     = Price of Coupon bond
+    - govt      = Government bond yields given as Nominal RateCurve
+    - swap      = Par swap rates given as Nominal RateCurve
+    - swap_spot = Swap spot rates given as RateCurve
+
+    Returns None if the Z-spread can not be solved
+     */
+    pub fn spreads(
+        &self,
+        price: f64,
+        govt: &RateCurve,
+        swap: &RateCurve,
+        swap_spot: &RateCurve,
+    ) -> Option<BondSpreads> {
+        Some(BondSpreads {
+            g_spread: self.g_spread(price, govt),
+            i_spread: self.i_spread(price, swap),
+            z_spread: self.z_spread(price, swap_spot)?,
+            asw_par: self.asw_par_par(price, swap_spot),
+            asw_market: self.asw_market_value(price, swap_spot),
+        })
+    }
+}
+
+#[cfg(test)]
+mod spreads_fn {
+    use super::super::rates::Rates;
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn spread_measures() {
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.05,
+            freq: 2.0,
+            t_life: 3.0,
+        };
+        let swap = RateCurve::NominalRateCurve {
+            rate: vec![0.020000, 0.024000, 0.027600, 0.030840, 0.033756, 0.036380],
+            freq: 2.0,
+        };
+        let Rates::SpotRates { rate: spot } = (Rates::ParRates { rate: swap.clone() }).to_spot()
+        else {
+            panic!("Spot rates expected")
+        };
+        let govt = swap.shift(-0.002);
+
+        let p0 = cb.price_ratecurve(&spot);
+        assert!(approx(cb.z_spread(p0, &spot).unwrap(), 0.0));
+        assert!(approx(cb.asw_par_par(p0, &spot), 0.0));
+
+        let p = cb.price_zspread(&spot, 0.012);
+        assert!(approx(cb.z_spread(p, &spot).unwrap(), 0.012));
+
+        assert!(cb.z_spread(-10.0, &spot).is_none());
+
+        let sp = cb.spreads(p, &govt, &swap, &spot).unwrap();
+        assert!(approx(sp.i_spread, 0.011882483112045407));
+        assert!(approx(sp.asw_par, 0.011930856045774468));
+        assert!(approx(sp.g_spread - sp.i_spread, 0.002));
+        assert!(approx(sp.asw_market, sp.asw_par * 100.0 / p));
+    }
+}
@@ -0,0 +1,392 @@
+/*!
+Implement Binomial interest rate tree modules for the financelib library
+
+Module      : financelib::fixedincomes::bonds::trees <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the recombining binomial interest rate trees of Black-Derman-Toy,
+Ho-Lee and Kalotay-Williams-Fabozzi, calibrated to the spot rates so that they are free of
+arbitrage, along with the backward induction used to value bonds with embedded options,
+caps and floors, the option adjusted spread and the effective duration.
+
+The rates in the tree are Nominal rates at the frequency of the spot RateCurve, with each
+node discounting over one period of 1 / freq, and the up and down moves taken with equal
+probability.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::CouponBond;
+use super::rates::{RateCurve, Rates};
+use serde::{Deserialize, Serialize};
+
+/**
+Enum defining the model of the short rate in the tree, with j the number of up moves at
+level i and dt = 1 / freq
+
+- BDT     => Black-Derman-Toy, r(i, j) = m(i) exp(2 vol(i) sqrt(dt) j) with the volatility of each level, the last one used beyond the vector
+- HoLee   => Ho-Lee, r(i, j) = m(i) + vol sqrt(dt) (2j - i) with the vol as absolute volatility
+- Kalotay => Kalotay-Williams-Fabozzi, r(i, j) = m(i) exp(2 vol sqrt(dt) j)
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TreeModel {
+    BDT(Vec<f64>),
+    HoLee(f64),
+    Kalotay(f64),
+}
+
+impl TreeModel {
+    /** Rate at node j of level i given the calibrated parameter m of the level */
+    fn rate(&self, i: usize, j: usize, m: f64, dt: f64) -> f64 {
+        match self {
+            Self::BDT(vol) => m * (2.0 * vol[i.min(vol.len() - 1)] * dt.sqrt() * j as f64).exp(),
+            Self::HoLee(vol) => m + vol * dt.sqrt() * (2.0 * j as f64 - i as f64),
+            Self::Kalotay(vol) => m * (2.0 * vol * dt.sqrt() * j as f64).exp(),
+        }
+    }
+}
+
+/**
+Enum defining the embedded option of a bond, exercisable at any coupon date on or after
+the first exercise time
+
+- Straight  => No embedded option
+- Callable  => Issuer may call the bond at price from time t_first
+- Putable   => Holder may put the bond at price from time t_first
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EmbeddedOption {
+    Straight,
+    Callable { price: f64, t_first: f64 },
+    Putable { price: f64, t_first: f64 },
+}
+
+/**
+BinomialTree : struct defining a recombining binomial interest rate tree
+
+- curve = Spot RateCurve as Nominal rates, to which the tree is calibrated
+- model = Model of the short rate
+- rates = Rates at each node, where level i has i + 1 nodes with j up moves
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinomialTree {
+    pub curve: RateCurve,
+    pub model: TreeModel,
+    pub rates: Vec<Vec<f64>>,
+}
+
+impl BinomialTree {
+    /**
+    Build the tree calibrated to the spot rates, so that it prices each zero coupon bond
+    on the curve exactly. The levels of the tree are given by the grid of the RateCurve.
+
+    - rates = Rates, converted to spot rates if needed
+    - model = Model of the short rate
+     */
+    pub fn calibrate(rates: &Rates, model: TreeModel) -> Self {
+        let curve = rates.to_spot().curve().to_nominal();
+        let freq = curve.freq();
+        let dt = 1.0 / freq;
+        let n = curve.rates().len();
+        let mut q = vec![1.0];
+        let mut tree: Vec<Vec<f64>> = Vec::with_capacity(n);
+        for i in 0..n {
+            let p = curve.pv(1.0, (i + 1) as f64 * dt);
+            let level = |m: f64| -> Vec<f64> { (0..=i).map(|j| model.rate(i, j, m, dt)).collect() };
+            let m = crate::newt_raph(
+                |m| {
+                    level(m)
+                        .iter()
+                        .zip(&q)
+                        .map(|(r, qj)| qj / (1.0 + r * dt))
+                        .sum::<f64>()
+                        - p
+                },
+                curve.rate_estim((i + 1) as f64 * dt),
+                1e-14,
+            )
+            .unwrap();
+            let rs = level(m);
+            let d: Vec<f64> = rs
+                .iter()
+                .zip(&q)
+                .map(|(r, qj)| qj / (1.0 + r * dt))
+                .collect();
+            q = (0..=i + 1)
+                .map(|j| {
+                    0.5 * (if j > 0 { d[j - 1] } else { 0.0 } + if j <= i { d[j] } else { 0.0 })
+                })
+                .collect();
+            tree.push(rs);
+        }
+        BinomialTree {
+            curve,
+            model,
+            rates: tree,
+        }
+    }
+
+    /** Length of each period of the tree */
+    pub fn dt(&self) -> f64 {
+        1.0 / self.curve.freq()
+    }
+
+    /**
+    Backward induction over the tree from level n to 0, where the value at each node is
+    first the continuation value, being the average of the values at the two following
+    nodes discounted at the node rate plus the spread, and is then updated by the node rule.
+
+    - n         = Level at which the induction starts, given as number of periods
+    - spread    = Spread added to the rate at each node while discounting, as the OAS
+    - terminal  = Value at node j of level n
+    - node      = Value at a node given as node(i, r, v) with level i, node rate r and continuation value v
+
+    Returns None if the tree has fewer than n levels
+     */
+    pub fn backward_induction(
+        &self,
+        n: usize,
+        spread: f64,
+        terminal: impl Fn(usize) -> f64,
+        node: impl Fn(usize, f64, f64) -> f64,
+    ) -> Option<f64> {
+        if n > self.rates.len() {
+            return None;
+        }
+        let dt = self.dt();
+        let mut v: Vec<f64> = (0..=n).map(terminal).collect();
+        for i in (0..n).rev() {
+            v = (0..=i)
+                .map(|j| {
+                    let r = self.rates[i][j];
+                    node(i, r, 0.5 * (v[j] + v[j + 1]) / (1.0 + (r + spread) * dt))
+                })
+                .collect();
+        }
+        Some(v[0])
+    }
+
+    /**
+    Price of a Coupon bond with an embedded option on the tree. The option is exercised
+    at the coupon dates on the value after the coupon.
+
+    - cb    = CouponBond
+    - opt   = Embedded option
+    - oas   = Option adjusted spread
+
+    Returns None if the bond does not pay coupons at the frequency of the tree or matures
+    beyond the last level of the tree
+     */
+    pub fn price_bond(&self, cb: &CouponBond, opt: EmbeddedOption, oas: f64) -> Option<f64> {
+        let dt = self.dt();
+        if !crate::approx(cb.freq * dt, 1.0) {
+            return None;
+        }
+        let n = (cb.t_life * cb.freq).round() as usize;
+        let c = cb.par * cb.c / cb.freq;
+        let exercise = |i: usize, v: f64| match opt {
+            EmbeddedOption::Straight => v,
+            EmbeddedOption::Callable { price, t_first } if i as f64 * dt >= t_first - 1e-9 => {
+                v.min(price)
+            }
+            EmbeddedOption::Putable { price, t_first } if i as f64 * dt >= t_first - 1e-9 => {
+                v.max(price)
+            }
+            _ => v,
+        };
+        self.backward_induction(
+            n,
+            oas,
+            |_| cb.par + c,
+            |i, _, v| if i == 0 { v } else { exercise(i, v) + c },
+        )
+    }
+
+    /**
+    Option adjusted spread of a Coupon bond with an embedded option given its price
+
+    - cb    = CouponBond
+    - opt   = Embedded option
+    - price = Price of the bond
+
+    Returns None if the bond cannot be priced on the tree or no spread gives the price
+     */
+    pub fn oas(&self, cb: &CouponBond, opt: EmbeddedOption, price: f64) -> Option<f64> {
+        self.price_bond(cb, opt, 0.0)?;
+        crate::newt_raph(|x| self.price_bond(cb, opt, x).unwrap() - price, 0.0, 1e-9)
+    }
+
+    /**
+    Effective duration and effective convexity of a Coupon bond with an embedded option,
+    with the tree recalibrated to the spot curve shifted up and down, keeping the OAS
+
+    - cb    = CouponBond
+    - opt   = Embedded option
+    - oas   = Option adjusted spread
+    - dy    = Shift in the spot curve
+
+    Returns None if the bond cannot be priced on the tree
+     */
+    pub fn effective_duration(
+        &self,
+        cb: &CouponBond,
+        opt: EmbeddedOption,
+        oas: f64,
+        dy: f64,
+    ) -> Option<(f64, f64)> {
+        let value = |s: f64| {
+            BinomialTree::calibrate(
+                &Rates::SpotRates {
+                    rate: self.curve.shift(s),
+                },
+                self.model.clone(),
+            )
+            .price_bond(cb, opt, oas)
+        };
+        let (p0, pu, pd) = (self.price_bond(cb, opt, oas)?, value(dy)?, value(-dy)?);
+        Some((
+            (pd - pu) / (2.0 * p0 * dy),
+            (pd + pu - 2.0 * p0) / (p0 * dy * dy),
+        ))
+    }
+
+    /**
+    Price of an Interest rate cap, with each caplet set on the rate at a node and paid
+    one period later
+
+    - notional  = Notional of the cap
+    - strike    = Cap rate
+    - t_life    = Life of the cap, where the first caplet is set at time 0
+
+    Returns None if the cap is longer than the tree
+     */
+    pub fn cap(&self, notional: f64, strike: f64, t_life: f64) -> Option<f64> {
+        self.capfloor(notional, t_life, |r| (r - strike).max(0.0))
+    }
+
+    /**
+    Price of an Interest rate floor, with each floorlet set on the rate at a node and
+    paid one period later
+
+    - notional  = Notional of the floor
+    - strike    = Floor rate
+    - t_life    = Life of the floor, where the first floorlet is set at time 0
+
+    Returns None if the floor is longer than the tree
+     */
+    pub fn floor(&self, notional: f64, strike: f64, t_life: f64) -> Option<f64> {
+        self.capfloor(notional, t_life, |r| (strike - r).max(0.0))
+    }
+
+    fn capfloor(&self, notional: f64, t_life: f64, payoff: impl Fn(f64) -> f64) -> Option<f64> {
+        let dt = self.dt();
+        self.backward_induction(
+            (t_life / dt).round() as usize,
+            0.0,
+            |_| 0.0,
+            |_, r, v| v + notional * dt * payoff(r) / (1.0 + r * dt),
+        )
+    }
+}
+
+#[cfg(test)]
+mod trees_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn binomial_trees() {
+        let rates = Rates::ParRates {
+            rate: RateCurve::EffectiveRateCurve {
+                rate: vec![0.0100, 0.0120, 0.0125, 0.0140, 0.0180],
+                freq: 1.0,
+            },
+        };
+        let kt = BinomialTree::calibrate(&rates, TreeModel::Kalotay(0.15));
+        assert!(approx(kt.rates[0][0], 0.01));
+        assert!(approx(kt.rates[1][1] / kt.rates[1][0], (0.3f64).exp()));
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.0180,
+            freq: 1.0,
+            t_life: 5.0,
+        };
+        assert!(approx(
+            kt.price_bond(&cb, EmbeddedOption::Straight, 0.0).unwrap(),
+            100.0
+        ));
+        let long = CouponBond { t_life: 6.0, ..cb };
+        assert_eq!(kt.price_bond(&long, EmbeddedOption::Straight, 0.0), None);
+        assert_eq!(kt.oas(&long, EmbeddedOption::Straight, 100.0), None);
+        let semi = CouponBond { freq: 2.0, ..cb };
+        assert_eq!(kt.price_bond(&semi, EmbeddedOption::Straight, 0.0), None);
+        assert_eq!(
+            kt.effective_duration(&semi, EmbeddedOption::Straight, 0.0, 1e-3),
+            None
+        );
+        assert_eq!(kt.cap(1e6, 0.02, 6.0), None);
+
+        let spot = Rates::SpotRates {
+            rate: RateCurve::NominalRateCurve {
+                rate: vec![
+                    0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+                ],
+                freq: 2.0,
+            },
+        };
+        let cb = CouponBond {
+            par: 100.0,
+            c: 0.04,
+            freq: 2.0,
+            t_life: 5.0,
+        };
+        let call = EmbeddedOption::Callable {
+            price: 100.0,
+            t_first: 1.0,
+        };
+        let put = EmbeddedOption::Putable {
+            price: 100.0,
+            t_first: 1.0,
+        };
+        for model in [
+            TreeModel::BDT(vec![0.10, 0.12, 0.15]),
+            TreeModel::HoLee(0.01),
+            TreeModel::Kalotay(0.15),
+        ] {
+            let tr = BinomialTree::calibrate(&spot, model);
+            let ps = tr.price_bond(&cb, EmbeddedOption::Straight, 0.0).unwrap();
+            assert!(approx(ps, cb.price_ratecurve(spot.curve())));
+            let (pc, pp) = (
+                tr.price_bond(&cb, call, 0.0).unwrap(),
+                tr.price_bond(&cb, put, 0.0).unwrap(),
+            );
+            assert!(pc < ps && pp > ps);
+
+            let cap = tr.cap(1e6, 0.03, 5.0).unwrap();
+            let swap: f64 = (0..10)
+                .map(|i| {
+                    let t = i as f64 / 2.0;
+                    1e6 * (spot.forward_rate(t, 0.5) - 0.03) / 2.0 * spot.curve().pv(1.0, t + 0.5)
+                })
+                .sum();
+            assert!(approx(cap - tr.floor(1e6, 0.03, 5.0).unwrap(), swap));
+
+            let oas = tr.oas(&cb, call, pc - 1.0).unwrap();
+            assert!(oas > 0.0 && approx(tr.price_bond(&cb, call, oas).unwrap(), pc - 1.0));
+
+            let (ds, _) = tr
+                .effective_duration(&cb, EmbeddedOption::Straight, 0.0, 1e-3)
+                .unwrap();
+            let (pu, pd) = (
+                cb.price_ratecurve(&spot.curve().shift(1e-3)),
+                cb.price_ratecurve(&spot.curve().shift(-1e-3)),
+            );
+            assert!((ds - (pd - pu) / (2.0 * ps * 1e-3)).abs() < 1e-4);
+            let (dc, cc) = tr.effective_duration(&cb, call, 0.0, 1e-3).unwrap();
+            assert!(dc < ds && cc < 0.0);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+/*!
+Implement Credit risk modules for the financelib library
+
+Module      : financelib::fixedincomes::credits <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the base modules of Credit risk like Hazard rates, Survival
+probabilities, Expected loss and risky discounting of Bonds and CDS.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::bonds::CouponBond;
+use super::bonds::rates::RateCurve;
+use serde::{Deserialize, Serialize};
+
+/** Expected loss = PD * LGD * EAD
+- pd  = Probability of default
+- lgd = Loss given default = 1 - Recovery rate
+- ead = Exposure at default
+*/
+pub fn expected_loss(pd: f64, lgd: f64, ead: f64) -> f64 {
+    pd * lgd * ead
+}
+
+/**
+HazardCurve : Piecewise constant hazard rates (default intensity)
+
+- tenor     = Vector of end of each segment in periods, in ascending order
+- hazard    = Vector of hazard rates applicable till the corresponding tenor
+
+The hazard rate of the last segment continues beyond the last tenor.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardCurve {
+    pub tenor: Vec<f64>,
+    pub hazard: Vec<f64>,
+}
+
+impl HazardCurve {
+    /**
+    Hazard rate at a time
+
+    - t = time given as period
+
+    Returns None if the curve has no hazard rates.
+     */
+    pub fn hazard_rate(&self, t: f64) -> Option<f64> {
+        let i = self.tenor.iter().take_while(|&&x| x < t).count();
+        self.hazard
+            .get(i.min(self.hazard.len().checked_sub(1)?))
+            .copied()
+    }
+
+    /**
+    Survival probability till time t = exp(-integral of hazard rate over (0, t))
+
+    - t = time given as period
+     */
+    pub fn survival(&self, t: f64) -> f64 {
+        let n = self.hazard.len();
+        let mut t0 = 0.0;
+        let mut h = 0.0;
+        for (i, (&ti, &hi)) in self.tenor.iter().zip(&self.hazard).enumerate() {
+            let t1 = if i == n - 1 { f64::MAX } else { ti };
+            h += hi * (t.min(t1) - t0).max(0.0);
+            t0 = t1;
+        }
+        (-h).exp()
+    }
+
+    /**
+    Cumulative probability of default till time t
+
+    - t = time given as period
+     */
+    pub fn default_prob(&self, t: f64) -> f64 {
+        1.0 - self.survival(t)
+    }
+
+    /**
+    Probability of default between t0 and t1 as seen today
+
+    - t0    = begin of period
+    - t1    = end of period
+     */
+    pub fn default_prob_between(&self, t0: f64, t1: f64) -> f64 {
+        self.survival(t0) - self.survival(t1)
+    }
+
+    /**
+    Expected loss on an exposure till time t
+
+    - t         = time given as period
+    - recovery  = Recovery rate
+    - ead       = Exposure at default
+     */
+    pub fn expected_loss(&self, t: f64, recovery: f64, ead: f64) -> f64 {
+        expected_loss(self.default_prob(t), 1.0 - recovery, ead)
+    }
+
+    /**
+    PV of a risky cash flow which is paid only if no default happens till time t
+
+    - c     = cash flow
+    - t     = time given as period
+    - rc    = risk-free discount RateCurve
+     */
+    pub fn risky_pv(&self, c: f64, t: f64, rc: &RateCurve) -> f64 {
+        rc.pv(c, t) * self.survival(t)
+    }
+
+    /**
+    Premium leg annuity (PV of 1 unit of spread) and protection leg of a CDS, per
+    unit notional. Accrued premium on default is taken as half the period.
+
+    - t_life    = Life of the CDS
+    - freq      = Frequency of premium payments per period
+    - recovery  = Recovery rate
+    - rc        = risk-free discount RateCurve
+     */
+    pub fn cds_legs(&self, t_life: f64, freq: f64, recovery: f64, rc: &RateCurve) -> (f64, f64) {
+        (1..=((t_life * freq).round() as usize))
+            .map(|i| {
+                let (t0, t1) = ((i - 1) as f64 / freq, i as f64 / freq);
+                let (df, q0, q1) = (rc.pv(1.0, t1), self.survival(t0), self.survival(t1));
+                (
+                    df * (q1 + (q0 - q1) / 2.0) / freq,
+                    df * (q0 - q1) * (1.0 - recovery),
+                )
+            })
+            .fold((0.0, 0.0), |(a, b), (x, y)| (a + x, b + y))
+    }
+
+    /**
+    Par spread of a CDS = protection leg / premium leg annuity
+
+    - t_life    = Life of the CDS
+    - freq      = Frequency of premium payments per period
+    - recovery  = Recovery rate
+    - rc        = risk-free discount RateCurve
+     */
+    pub fn cds_spread(&self, t_life: f64, freq: f64, recovery: f64, rc: &RateCurve) -> f64 {
+        let (annuity, protection) = self.cds_legs(t_life, freq, recovery, rc);
+        protection / annuity
+    }
+
+    /**
+    Solve the hazard rate of each new segment so that the given target is met.
+    Returns None if any segment has no solution.
+    */
+    fn bootstrap<T>(
+        quotes: &[(f64, T)],
+        target: impl Fn(&HazardCurve, &T) -> f64,
+    ) -> Option<HazardCurve> {
+        let mut hc = HazardCurve {
+            tenor: vec![],
+            hazard: vec![],
+        };
+        for (t, q) in quotes {
+            hc.tenor.push(*t);
+            hc.hazard.push(0.01);
+            let n = hc.hazard.len() - 1;
+            hc.hazard[n] = crate::newt_raph(
+                |h| {
+                    let mut hx = hc.clone();
+                    hx.hazard[n] = h;
+                    target(&hx, q)
+                },
+                0.01,
+                1e-8,
+            )?;
+        }
+        Some(hc)
+    }
+
+    /**
+    Bootstrap piecewise constant hazard rates from par CDS spreads
+
+    - spreads   = Vector of (tenor, par CDS spread) in ascending order of tenor
+    - freq      = Frequency of premium payments per period
+    - recovery  = Recovery rate
+    - rc        = risk-free discount RateCurve
+
+    Returns None if a spread can not be matched by any hazard rate.
+     */
+    pub fn from_cds_spreads(
+        spreads: &[(f64, f64)],
+        freq: f64,
+        recovery: f64,
+        rc: &RateCurve,
+    ) -> Option<HazardCurve> {
+        Self::bootstrap(spreads, |hc, &s| {
+            let (annuity, protection) =
+                hc.cds_legs(hc.tenor[hc.tenor.len() - 1], freq, recovery, rc);
+            s * annuity - protection
+        })
+    }
+
+    /**
+    Bootstrap piecewise constant hazard rates from risky Coupon bond prices.
+    The maturity of each bond is taken as the tenor of its segment.
+
+    - bonds     = Vector of (CouponBond, price) in ascending order of maturity
+    - recovery  = Recovery rate as a fraction of par
+    - rc        = risk-free discount RateCurve
+
+    Returns None if a price can not be matched by any hazard rate.
+     */
+    pub fn from_bond_prices(
+        bonds: &[(CouponBond, f64)],
+        recovery: f64,
+        rc: &RateCurve,
+    ) -> Option<HazardCurve> {
+        let quotes: Vec<(f64, (CouponBond, f64))> =
+            bonds.iter().map(|&(cb, p)| (cb.t_life, (cb, p))).collect();
+        Self::bootstrap(&quotes, |hc, (cb, p)| cb.price_credit(rc, hc, recovery) - p)
+    }
+}
+
+impl CouponBond {
+    /**
+    Price of Coupon bond adjusted for the credit risk of the issuer. Coupons and par
+    are paid on survival, while the recovery on par is paid at the end of the period of
+    default.
+
+    - rc        = risk-free discount RateCurve
+    - hc        = HazardCurve of the issuer
+    - recovery  = Recovery rate as a fraction of par
+     */
+    pub fn price_credit(&self, rc: &RateCurve, hc: &HazardCurve, recovery: f64) -> f64 {
+        self.generate_cashflow()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let (t0, t1) = (i as f64 / self.freq, (i + 1) as f64 / self.freq);
+                hc.risky_pv(c, t1, rc)
+                    + rc.pv(recovery * self.par, t1) * hc.default_prob_between(t0, t1)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod credits_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn hazard_curves() {
+        assert!(approx(expected_loss(0.02, 0.6, 1_000_000.0), 12_000.0));
+
+        let hc = HazardCurve {
+            tenor: vec![1.0, 3.0],
+            hazard: vec![0.02, 0.03],
+        };
+        assert!(approx(hc.survival(2.0), (-0.05f64).exp()));
+        assert!(approx(hc.survival(4.0), (-0.11f64).exp()));
+        assert_eq!(hc.hazard_rate(0.5), Some(0.02));
+        assert_eq!(hc.hazard_rate(5.0), Some(0.03));
+        let empty = HazardCurve {
+            tenor: vec![],
+            hazard: vec![],
+        };
+        assert_eq!(empty.hazard_rate(1.0), None);
+        assert!(approx(
+            hc.expected_loss(1.0, 0.4, 100.0),
+            0.6 * 100.0 * (1.0 - (-0.02f64).exp())
+        ));
+
+        let rc = RateCurve::NominalRateCurve {
+            rate: vec![
+                0.0200, 0.0225, 0.0250, 0.0270, 0.0290, 0.0305, 0.0315, 0.0320, 0.0325, 0.0330,
+            ],
+            freq: 2.0,
+        };
+
+        let spreads = vec![(1.0, 0.0100), (3.0, 0.0120), (5.0, 0.0150)];
+        let hc = HazardCurve::from_cds_spreads(&spreads, 4.0, 0.4, &rc).unwrap();
+        spreads
+            .iter()
+            .for_each(|&(t, s)| assert!(approx(hc.cds_spread(t, 4.0, 0.4, &rc), s)));
+        assert!(approx(hc.hazard[0], 0.01666669077938375));
+        assert!(approx(hc.hazard[2], 0.03374623874394502));
+        assert!(HazardCurve::from_cds_spreads(&[(1.0, 10.0)], 4.0, 0.4, &rc).is_none());
+
+        let bonds: Vec<(CouponBond, f64)> = [2.0, 5.0]
+            .iter()
+            .map(|&t_life| {
+                let cb = CouponBond {
+                    par: 100.0,
+                    c: 0.05,
+                    freq: 2.0,
+                    t_life,
+                };
+                (cb, cb.price_credit(&rc, &hc, 0.4))
+            })
+            .collect();
+        let hb = HazardCurve::from_bond_prices(&bonds, 0.4, &rc).unwrap();
+        bonds
+            .iter()
+            .for_each(|(cb, p)| assert!(approx(cb.price_credit(&rc, &hb, 0.4), *p)));
+        assert!(bonds[1].1 < bonds[1].0.price_ratecurve(&rc));
+    }
+}
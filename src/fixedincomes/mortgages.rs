@@ -0,0 +1,264 @@
+/*!
+Implement Mortgage pass-through modules for the financelib library
+
+Module      : financelib::fixedincomes::mortgages <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the monthly cash flows of a Mortgage pass-through security with
+prepayments given as CPR, SMM or PSA speeds, along with its price, yield, weighted
+average life and effective duration.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use serde::{Deserialize, Serialize};
+
+/**
+Enum defining the prepayment speed of a mortgage pool
+
+- CPR => Conditional prepayment rate, given as annual rate
+- SMM => Single monthly mortality, given as monthly rate
+- PSA => PSA speed given in percent, e.g. 100 PSA => CPR ramps up by 0.2% a month to 6% at month 30
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Prepayment {
+    CPR(f64),
+    SMM(f64),
+    PSA(f64),
+}
+
+/** SMM from CPR = 1 - (1 - CPR)^(1/12) */
+pub fn cpr_to_smm(cpr: f64) -> f64 {
+    1.0 - (1.0 - cpr).powf(1.0 / 12.0)
+}
+
+/** CPR from SMM = 1 - (1 - SMM)^12 */
+pub fn smm_to_cpr(smm: f64) -> f64 {
+    1.0 - (1.0 - smm).powi(12)
+}
+
+impl Prepayment {
+    /**
+    Single monthly mortality in a month
+
+    - age = Age of the mortgages in months at the end of the month, starting from 1
+     */
+    pub fn smm(&self, age: usize) -> f64 {
+        match *self {
+            Self::CPR(x) => cpr_to_smm(x),
+            Self::SMM(x) => x,
+            Self::PSA(x) => cpr_to_smm(0.06 * (age.min(30) as f64 / 30.0) * x / 100.0),
+        }
+    }
+
+    /**
+    Prepayment speed scaled by a factor
+
+    - k = Scaling factor, e.g. 1.2 => 20% faster speed
+     */
+    pub fn scale(&self, k: f64) -> Prepayment {
+        match *self {
+            Self::CPR(x) => Self::CPR(x * k),
+            Self::SMM(x) => Self::SMM(x * k),
+            Self::PSA(x) => Self::PSA(x * k),
+        }
+    }
+}
+
+/**
+Cash flow of a mortgage pool in a month
+
+- month                 = Month of the cash flow, starting from 1
+- balance_begin         = Pool balance at the beginning of the month
+- scheduled_principal   = Scheduled principal repayment
+- prepaid_principal     = Prepaid principal
+- interest              = Interest net of servicing fee passed to the investors
+- balance_end           = Pool balance at the end of the month
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct MortgageCashFlow {
+    pub month: usize,
+    pub balance_begin: f64,
+    pub scheduled_principal: f64,
+    pub prepaid_principal: f64,
+    pub interest: f64,
+    pub balance_end: f64,
+}
+
+impl MortgageCashFlow {
+    /** Total principal repaid in the month */
+    pub fn principal(&self) -> f64 {
+        self.scheduled_principal + self.prepaid_principal
+    }
+
+    /** Total cash flow to the investors in the month */
+    pub fn total(&self) -> f64 {
+        self.principal() + self.interest
+    }
+}
+
+/**
+PassThrough : struct defining a Mortgage pass-through security
+
+- balance       = Current pool balance
+- wac           = Weighted average coupon of the mortgages
+- wam           = Weighted average maturity of the mortgages in months
+- servicing_fee = Servicing and guarantee fee as annual rate, so the net coupon is wac - servicing_fee
+- age           = Weighted average age of the mortgages in months, used for the PSA ramp
+ */
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PassThrough {
+    pub balance: f64,
+    pub wac: f64,
+    pub wam: usize,
+    pub servicing_fee: f64,
+    pub age: usize,
+}
+
+impl PassThrough {
+    /**
+    Pass-through rate = wac - servicing_fee
+     */
+    pub fn net_coupon(&self) -> f64 {
+        self.wac - self.servicing_fee
+    }
+
+    /**
+    Monthly cash flows of the pool till it is fully repaid
+
+    - pp = Prepayment speed
+     */
+    pub fn cashflows(&self, pp: Prepayment) -> Vec<MortgageCashFlow> {
+        let mut bal = self.balance;
+        (1..=self.wam)
+            .map_while(|month| {
+                if bal <= 0.0 {
+                    return None;
+                }
+                let n = (self.wam - month + 1) as f64;
+                let sched = -crate::pmt(self.wac, n / 12.0, 12.0, bal, 0.0) - bal * self.wac / 12.0;
+                let prepaid = pp.smm(self.age + month) * (bal - sched);
+                let cf = MortgageCashFlow {
+                    month,
+                    balance_begin: bal,
+                    scheduled_principal: sched,
+                    prepaid_principal: prepaid,
+                    interest: bal * self.net_coupon() / 12.0,
+                    balance_end: bal - sched - prepaid,
+                };
+                bal = cf.balance_end;
+                Some(cf)
+            })
+            .collect()
+    }
+
+    /**
+    Price of the pass-through given as PV of the monthly cash flows
+
+    - yld   = Yield as nominal rate compounded monthly
+    - pp    = Prepayment speed
+     */
+    pub fn price(&self, yld: f64, pp: Prepayment) -> f64 {
+        self.cashflows(pp)
+            .iter()
+            .map(|x| crate::pvm(yld, x.month as f64 / 12.0, 12.0, x.total()))
+            .sum()
+    }
+
+    /**
+    Yield of the pass-through as nominal rate compounded monthly
+
+    - price = Price of the pass-through
+    - pp    = Prepayment speed
+     */
+    pub fn yld(&self, price: f64, pp: Prepayment) -> f64 {
+        crate::newt_raph(|y| self.price(y, pp) - price, self.wac, 1e-8).unwrap()
+    }
+
+    /**
+    Weighted average life in years = Sum of time * principal / Sum of principal
+
+    - pp = Prepayment speed
+     */
+    pub fn wal(&self, pp: Prepayment) -> f64 {
+        let (tp, p) = self.cashflows(pp).iter().fold((0.0, 0.0), |(tp, p), x| {
+            (
+                tp + x.month as f64 / 12.0 * x.principal(),
+                p + x.principal(),
+            )
+        });
+        tp / p
+    }
+
+    /**
+    Effective duration with the prepayment speed scaled up when the rates fall and
+    scaled down when the rates rise.
+
+    - yld   = Yield as nominal rate compounded monthly
+    - pp    = Prepayment speed at yld
+    - dy    = Shock to the yield
+    - shock = Fraction by which the speed changes, e.g. 0.2 => 20% faster for yld - dy
+     */
+    pub fn effective_duration(&self, yld: f64, pp: Prepayment, dy: f64, shock: f64) -> f64 {
+        let pd = self.price(yld - dy, pp.scale(1.0 + shock));
+        let pu = self.price(yld + dy, pp.scale(1.0 - shock));
+        (pd - pu) / (2.0 * self.price(yld, pp) * dy)
+    }
+
+    /**
+    Effective convexity with the prepayment speed scaled up when the rates fall and
+    scaled down when the rates rise.
+
+    - yld   = Yield as nominal rate compounded monthly
+    - pp    = Prepayment speed at yld
+    - dy    = Shock to the yield
+    - shock = Fraction by which the speed changes, e.g. 0.2 => 20% faster for yld - dy
+     */
+    pub fn effective_convexity(&self, yld: f64, pp: Prepayment, dy: f64, shock: f64) -> f64 {
+        let p0 = self.price(yld, pp);
+        let pd = self.price(yld - dy, pp.scale(1.0 + shock));
+        let pu = self.price(yld + dy, pp.scale(1.0 - shock));
+        (pd + pu - 2.0 * p0) / (p0 * dy * dy)
+    }
+}
+
+#[cfg(test)]
+mod mortgages_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn pass_throughs() {
+        assert!(approx(Prepayment::PSA(100.0).smm(30), cpr_to_smm(0.06)));
+        assert!(approx(Prepayment::PSA(150.0).smm(10), cpr_to_smm(0.03)));
+        assert!(approx(smm_to_cpr(cpr_to_smm(0.08)), 0.08));
+
+        let pt = PassThrough {
+            balance: 400_000_000.0,
+            wac: 0.08125,
+            wam: 357,
+            servicing_fee: 0.00625,
+            age: 3,
+        };
+        let cf = pt.cashflows(Prepayment::PSA(165.0));
+        assert_eq!(cf.len(), 357);
+        assert!((cf[0].scheduled_principal - 267_534.91).abs() < 0.01);
+        assert!((cf[0].prepaid_principal - 442_388.58).abs() < 0.01);
+        assert!(approx(cf[0].interest, 400_000_000.0 * 0.075 / 12.0));
+        assert!(approx(cf.iter().map(|x| x.principal()).sum(), pt.balance));
+        assert!(cf[356].balance_end.abs() < 1e-4);
+
+        let p = pt.price(0.07, Prepayment::PSA(165.0));
+        assert!(approx(pt.yld(p, Prepayment::PSA(165.0)), 0.07));
+        assert!(approx(pt.price(0.075, Prepayment::SMM(0.0)), pt.balance));
+        assert!(pt.wal(Prepayment::PSA(300.0)) < pt.wal(Prepayment::PSA(165.0)));
+
+        let d0 = pt.effective_duration(0.07, Prepayment::PSA(165.0), 0.005, 0.0);
+        let d1 = pt.effective_duration(0.07, Prepayment::PSA(165.0), 0.005, 0.3);
+        assert!(d1 < d0 && d1 > 0.0);
+        assert!(pt.effective_convexity(0.07, Prepayment::PSA(165.0), 0.005, 0.3) < 0.0);
+    }
+}
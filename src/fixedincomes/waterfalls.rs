@@ -0,0 +1,507 @@
+/*!
+Implement Structured finance waterfall modules for the financelib library
+
+Module      : financelib::fixedincomes::waterfalls <br>
+Copyright   : (c) 2024 Kishaloy Neogi <br>
+License     : MIT <br>
+Maintainer  : Kishaloy Neogi <br>
+Email       : <nkishaloy@yahoo.com>
+
+The module describes the payment waterfall of CMO and ABS deals, which allocates the
+monthly cash flows of a collateral pool to the tranches. It supports sequential and
+pro-rata principal, interest by tranche coupon, accrual (Z) tranches, PAC / support
+tranches, overcollateralization tests and bottom-up loss allocation.
+
+You may see the github repository at <https://github.com/n-kishaloy/financelib>
+*/
+
+use super::mortgages::{MortgageCashFlow, PassThrough, Prepayment};
+use chrono::{Months, naive::NaiveDate as NDt};
+use serde::{Deserialize, Serialize};
+
+/**
+Cash flow of the collateral pool in a month
+
+- principal = Principal collected, scheduled and prepaid
+- interest  = Interest collected net of fees
+- loss      = Principal lost on defaults
+ */
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PoolCashFlow {
+    pub principal: f64,
+    pub interest: f64,
+    pub loss: f64,
+}
+
+impl From<&MortgageCashFlow> for PoolCashFlow {
+    fn from(x: &MortgageCashFlow) -> Self {
+        PoolCashFlow {
+            principal: x.principal(),
+            interest: x.interest,
+            loss: 0.0,
+        }
+    }
+}
+
+/**
+Enum defining the kind of tranche
+
+- Standard  => Receives interest and principal as per the principal rule
+- Accrual   => Z tranche, where interest accrues to the balance and is paid as principal to other tranches till they are retired
+- PAC       => Planned amortization class with the scheduled balance at the end of each month
+- Support   => Absorbs the prepayment variability of the PAC tranches
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TrancheKind {
+    Standard,
+    Accrual,
+    PAC(Vec<f64>),
+    Support,
+}
+
+/**
+Enum defining the allocation of principal among the Standard and Support tranches
+
+- Sequential    => In order of seniority
+- ProRata       => In proportion to the outstanding balance
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrincipalRule {
+    Sequential,
+    ProRata,
+}
+
+/**
+Tranche : struct defining a tranche of the deal
+
+- balance   = Original balance
+- coupon    = Coupon rate as annual rate paid monthly
+- kind      = Kind of tranche
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tranche {
+    pub balance: f64,
+    pub coupon: f64,
+    pub kind: TrancheKind,
+}
+
+/**
+Monthly cash flows of a tranche
+
+- dates     = Payment dates
+- interest  = Interest paid
+- principal = Principal paid
+- writedown = Principal written down for losses
+- balance   = Balance at the end of the month
+- shortfall = Interest due but unpaid at the end of the month, carried forward
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TrancheCashFlows {
+    pub dates: Vec<NDt>,
+    pub interest: Vec<f64>,
+    pub principal: Vec<f64>,
+    pub writedown: Vec<f64>,
+    pub balance: Vec<f64>,
+    pub shortfall: Vec<f64>,
+}
+
+impl TrancheCashFlows {
+    /** Total cash flow paid in each month */
+    pub fn total(&self) -> Vec<f64> {
+        self.interest
+            .iter()
+            .zip(&self.principal)
+            .map(|(i, p)| i + p)
+            .collect()
+    }
+
+    /**
+    Price of the tranche as the NPV of its cash flows
+
+    - r     = Discount rate as effective annual rate
+    - d0    = Settlement date
+     */
+    pub fn price(&self, r: f64, d0: NDt) -> f64 {
+        crate::xnpv(r, &self.dates, d0, &self.total())
+    }
+
+    /**
+    Yield of the tranche as effective annual rate
+
+    - price = Price of the tranche
+    - d0    = Settlement date
+     */
+    pub fn yld(&self, price: f64, d0: NDt) -> f64 {
+        crate::newt_raph(|r| self.price(r, d0) - price, 0.05, 1e-8).unwrap()
+    }
+}
+
+/**
+Waterfall : struct defining the payment waterfall of a deal
+
+- tranches          = Tranches given in order of seniority
+- rule              = Allocation of principal among the Standard and Support tranches
+- oc_target         = Target ratio of collateral balance to tranche balance. Excess interest is paid as principal while the test fails
+- collateral        = Collateral balance at the start
+- dt_begin          = Start date of the deal, with payments monthly thereafter
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waterfall {
+    pub tranches: Vec<Tranche>,
+    pub rule: PrincipalRule,
+    pub oc_target: Option<f64>,
+    pub collateral: f64,
+    pub dt_begin: NDt,
+}
+
+/**
+Cash flows of a deal given per tranche along with the residual paid to the equity
+ */
+#[derive(Debug, Clone)]
+pub struct WaterfallCashFlows {
+    pub tranches: Vec<TrancheCashFlows>,
+    pub residual: Vec<f64>,
+}
+
+/**
+PAC schedule as the balance at the end of each month, taking the lower of the principal
+of the pool at the lower and upper band speeds
+
+- pool      = Collateral pool
+- lower     = Lower band of prepayment speed
+- upper     = Upper band of prepayment speed
+- balance   = Original balance of the PAC tranche
+ */
+pub fn pac_schedule(
+    pool: &PassThrough,
+    lower: Prepayment,
+    upper: Prepayment,
+    balance: f64,
+) -> Vec<f64> {
+    let (cl, cu) = (pool.cashflows(lower), pool.cashflows(upper));
+    let mut bal = balance;
+    (0..cl.len().max(cu.len()))
+        .map(|i| {
+            let pl = cl.get(i).map_or(0.0, |x| x.principal());
+            let pu = cu.get(i).map_or(0.0, |x| x.principal());
+            bal = (bal - pl.min(pu)).max(0.0);
+            bal
+        })
+        .collect()
+}
+
+/** Pay principal to the tranches sequentially and return the amount left */
+fn pay_sequential(bal: &mut [f64], prin: &mut [f64], ids: &[usize], mut amt: f64) -> f64 {
+    for &i in ids {
+        let x = amt.min(bal[i]);
+        (bal[i], prin[i], amt) = (bal[i] - x, prin[i] + x, amt - x);
+    }
+    amt
+}
+
+/** Pay principal to the tranches pro-rata and return the amount left */
+fn pay_pro_rata(bal: &mut [f64], prin: &mut [f64], ids: &[usize], amt: f64) -> f64 {
+    let tot: f64 = ids.iter().map(|&i| bal[i]).sum();
+    if tot <= amt {
+        return pay_sequential(bal, prin, ids, amt);
+    }
+    for &i in ids {
+        let x = amt * bal[i] / tot;
+        (bal[i], prin[i]) = (bal[i] - x, prin[i] + x);
+    }
+    0.0
+}
+
+impl Waterfall {
+    /**
+    Allocate the cash flows of the collateral pool to the tranches. Each month, interest
+    is paid by seniority, with the Z tranche accreting only the interest available at
+    its seniority, which is released as principal. Interest left unpaid is carried
+    forward without interest as a shortfall of the tranche, which is paid first out of
+    the interest due to the tranche in the later months. The principal is paid first to the
+    PAC tranches up to their schedule, then to the Standard and Support tranches as
+    per the rule, then to the PAC tranches beyond schedule and finally to the Z
+    tranche. Losses not covered by overcollateralization are written down bottom-up.
+
+    - pool = Monthly cash flows of the collateral pool
+     */
+    pub fn run(&self, pool: &[PoolCashFlow]) -> WaterfallCashFlows {
+        let nt = self.tranches.len();
+        let mut bal: Vec<f64> = self.tranches.iter().map(|x| x.balance).collect();
+        let mut sf = vec![0.0; nt];
+        let mut coll = self.collateral;
+        let mut cf = vec![TrancheCashFlows::default(); nt];
+        let mut residual = vec![];
+
+        let ids = |f: &dyn Fn(&TrancheKind) -> bool| -> Vec<usize> {
+            (0..nt).filter(|&i| f(&self.tranches[i].kind)).collect()
+        };
+        let pac = ids(&|k| matches!(k, TrancheKind::PAC(_)));
+        let others = ids(&|k| matches!(k, TrancheKind::Standard | TrancheKind::Support));
+        let accrual = ids(&|k| *k == TrancheKind::Accrual);
+
+        for (m, pc) in pool.iter().enumerate() {
+            let mut int = vec![0.0; nt];
+            let mut prin = vec![0.0; nt];
+            let mut wd = vec![0.0; nt];
+            coll -= pc.principal + pc.loss;
+
+            // Interest by seniority with accretion of the Z tranches
+            let z_live = (0..nt).any(|i| !accrual.contains(&i) && bal[i] > 1e-9);
+            let mut avail = pc.interest;
+            let mut accreted = 0.0;
+            for i in 0..nt {
+                let due = sf[i] + bal[i] * self.tranches[i].coupon / 12.0;
+                let x = due.min(avail);
+                (avail, sf[i]) = (avail - x, due - x);
+                if z_live && accrual.contains(&i) {
+                    bal[i] += x;
+                    accreted += x;
+                } else {
+                    int[i] = x;
+                }
+            }
+            let mut amt = pc.principal + accreted;
+
+            // Turbo principal from excess interest while the OC test fails
+            let mut excess = avail;
+            if let Some(oc) = self.oc_target {
+                let tot: f64 = bal.iter().sum::<f64>() - amt;
+                let deficit = (oc * tot - coll).max(0.0) / oc;
+                let turbo = excess.min(deficit);
+                (amt, excess) = (amt + turbo, excess - turbo);
+            }
+
+            for &i in &pac {
+                if let TrancheKind::PAC(sch) = &self.tranches[i].kind {
+                    let target = sch.get(m).copied().unwrap_or(0.0);
+                    let x = amt.min((bal[i] - target).max(0.0));
+                    (bal[i], prin[i], amt) = (bal[i] - x, prin[i] + x, amt - x);
+                }
+            }
+            amt = match self.rule {
+                PrincipalRule::Sequential => pay_sequential(&mut bal, &mut prin, &others, amt),
+                PrincipalRule::ProRata => pay_pro_rata(&mut bal, &mut prin, &others, amt),
+            };
+            amt = pay_sequential(&mut bal, &mut prin, &pac, amt);
+            amt = pay_sequential(&mut bal, &mut prin, &accrual, amt);
+
+            // Losses written down bottom-up when the collateral falls short
+            let mut short = bal.iter().sum::<f64>() - coll.max(0.0);
+            for i in (0..nt).rev() {
+                if short <= 1e-9 {
+                    break;
+                }
+                let x = short.min(bal[i]);
+                (bal[i], wd[i], short) = (bal[i] - x, x, short - x);
+            }
+
+            let dt = self.dt_begin + Months::new(m as u32 + 1);
+            for i in 0..nt {
+                cf[i].dates.push(dt);
+                cf[i].interest.push(int[i]);
+                cf[i].principal.push(prin[i]);
+                cf[i].writedown.push(wd[i]);
+                cf[i].balance.push(bal[i]);
+                cf[i].shortfall.push(sf[i]);
+            }
+            residual.push(excess + amt);
+        }
+
+        WaterfallCashFlows {
+            tranches: cf,
+            residual,
+        }
+    }
+}
+
+#[cfg(test)]
+mod waterfalls_fn {
+    use super::*;
+    use crate::approx;
+
+    #[test]
+    fn waterfalls() {
+        let pool = PassThrough {
+            balance: 400_000_000.0,
+            wac: 0.08125,
+            wam: 357,
+            servicing_fee: 0.00625,
+            age: 3,
+        };
+        let pcf: Vec<PoolCashFlow> = pool
+            .cashflows(Prepayment::PSA(165.0))
+            .iter()
+            .map(PoolCashFlow::from)
+            .collect();
+        let d0 = NDt::from_ymd_opt(2024, 1, 1).unwrap();
+        let tr = |balance, kind| Tranche {
+            balance,
+            coupon: 0.075,
+            kind,
+        };
+
+        let seq = Waterfall {
+            tranches: vec![
+                tr(194_500_000.0, TrancheKind::Standard),
+                tr(36_000_000.0, TrancheKind::Standard),
+                tr(96_500_000.0, TrancheKind::Standard),
+                tr(73_000_000.0, TrancheKind::Standard),
+            ],
+            rule: PrincipalRule::Sequential,
+            oc_target: None,
+            collateral: pool.balance,
+            dt_begin: d0,
+        };
+        let wf = seq.run(&pcf);
+        assert!(approx(
+            wf.tranches[0].interest[0],
+            194_500_000.0 * 0.075 / 12.0
+        ));
+        assert!(approx(wf.tranches[0].principal[0], pcf[0].principal));
+        assert_eq!(wf.tranches[1].principal[0], 0.0);
+        wf.tranches.iter().zip(&seq.tranches).for_each(|(c, t)| {
+            assert!(approx(c.principal.iter().sum(), t.balance));
+            assert!(approx(
+                c.price(crate::nom_eff_rate(0.075, 12.0), d0),
+                t.balance
+            ));
+        });
+        let y = wf.tranches[3].yld(0.95 * 73_000_000.0, d0);
+        assert!(approx(wf.tranches[3].price(y, d0), 0.95 * 73_000_000.0));
+
+        let z = Waterfall {
+            tranches: vec![
+                tr(300_000_000.0, TrancheKind::Standard),
+                tr(100_000_000.0, TrancheKind::Accrual),
+            ],
+            ..seq.clone()
+        };
+        let wz = z.run(&pcf);
+        assert_eq!(wz.tranches[1].interest[0], 0.0);
+        assert!(approx(
+            wz.tranches[1].balance[0],
+            100_000_000.0 * (1.0 + 0.075 / 12.0)
+        ));
+        assert!(approx(
+            wz.tranches[0].principal[0],
+            pcf[0].principal + 100_000_000.0 * 0.075 / 12.0
+        ));
+        let short = Waterfall {
+            tranches: vec![
+                tr(90.0, TrancheKind::Standard),
+                tr(10.0, TrancheKind::Accrual),
+            ],
+            collateral: 100.0,
+            ..seq.clone()
+        };
+        [(0.5, 0.0), (0.6, 0.6 - 90.0 * 0.075 / 12.0)]
+            .iter()
+            .for_each(|&(interest, acc)| {
+                let ws = short.run(&[PoolCashFlow {
+                    principal: 5.0,
+                    interest,
+                    loss: 0.0,
+                }]);
+                assert!(approx(ws.tranches[1].balance[0], 10.0 + acc));
+                assert!(approx(ws.tranches[0].balance[0], 85.0 - acc));
+                assert!(ws.tranches.iter().all(|x| x.writedown[0] == 0.0));
+            });
+
+        // Interest short in the first month is paid first in the second month
+        let ws = seq.run(&[
+            PoolCashFlow {
+                principal: 0.0,
+                interest: 1_000_000.0,
+                loss: 0.0,
+            },
+            PoolCashFlow {
+                principal: 0.0,
+                interest: 1_500_000.0,
+                loss: 0.0,
+            },
+        ]);
+        let due = 194_500_000.0 * 0.075 / 12.0;
+        assert!(approx(ws.tranches[0].interest[0], 1_000_000.0));
+        assert!(approx(ws.tranches[0].shortfall[0], due - 1_000_000.0));
+        assert!(approx(
+            ws.tranches[1].shortfall[0],
+            36_000_000.0 * 0.075 / 12.0
+        ));
+        assert!(approx(ws.tranches[0].interest[1], 2.0 * due - 1_000_000.0));
+        assert_eq!(ws.tranches[0].shortfall[1], 0.0);
+        assert!(approx(
+            ws.tranches[1].interest[1],
+            1_500_000.0 - ws.tranches[0].interest[1]
+        ));
+        assert!(approx(
+            ws.tranches[1].shortfall[1],
+            2.0 * 36_000_000.0 * 0.075 / 12.0 - ws.tranches[1].interest[1]
+        ));
+
+        let sch = pac_schedule(
+            &pool,
+            Prepayment::PSA(90.0),
+            Prepayment::PSA(300.0),
+            243_800_000.0,
+        );
+        let pac = Waterfall {
+            tranches: vec![
+                tr(243_800_000.0, TrancheKind::PAC(sch.clone())),
+                tr(156_200_000.0, TrancheKind::Support),
+            ],
+            ..seq.clone()
+        };
+        [Prepayment::PSA(100.0), Prepayment::PSA(250.0)]
+            .iter()
+            .for_each(|&pp| {
+                let pcf: Vec<PoolCashFlow> =
+                    pool.cashflows(pp).iter().map(PoolCashFlow::from).collect();
+                let wp = pac.run(&pcf);
+                let n = wp.tranches[0]
+                    .balance
+                    .iter()
+                    .position(|&b| b < 1e-6)
+                    .unwrap();
+                assert!(
+                    wp.tranches[0]
+                        .balance
+                        .iter()
+                        .zip(&sch)
+                        .all(|(b, s)| approx(*b, *s))
+                );
+                assert_eq!(n, sch.iter().position(|&b| b < 1e-6).unwrap());
+            });
+
+        let abs = Waterfall {
+            tranches: vec![
+                tr(80.0, TrancheKind::Standard),
+                tr(10.0, TrancheKind::Standard),
+                tr(5.0, TrancheKind::Standard),
+            ],
+            rule: PrincipalRule::ProRata,
+            oc_target: Some(1.05),
+            collateral: 100.0,
+            dt_begin: d0,
+        };
+        let pcf = vec![
+            PoolCashFlow {
+                principal: 10.0,
+                interest: 1.0,
+                loss: 0.0,
+            },
+            PoolCashFlow {
+                principal: 10.0,
+                interest: 1.0,
+                loss: 12.0,
+            },
+        ];
+        let wa = abs.run(&pcf);
+        assert!(approx(wa.tranches[1].principal[0], 10.0 * 10.0 / 95.0));
+        assert_eq!(wa.tranches[0].writedown[1], 0.0);
+        assert!(wa.tranches[2].writedown[1] > 0.0);
+        let tot: f64 = wa.tranches.iter().map(|x| x.balance[1]).sum();
+        assert!(approx(tot, 100.0 - 20.0 - 12.0));
+        assert!(wa.residual[1] < 1.0 - wa.tranches.iter().map(|x| x.interest[1]).sum::<f64>());
+    }
+}
@@ -19,8 +19,10 @@ pub mod fixedincomes;
 pub mod statements;
 pub mod valuations;
 
-use chrono::{Datelike, naive::NaiveDate as NDt};
-use serde::{Deserialize, Serialize};
+use argmin::core::{CostFunction, Executor, State};
+use argmin::solver::neldermead::NelderMead;
+use chrono::{Datelike, Weekday, naive::NaiveDate as NDt};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 // use time::util::is_leap_year;
 use DayCountConvention::*;
 
@@ -66,6 +68,7 @@ Enum defining different Days convention
 - ACT360 => Actual nos of days / 360
 - ACT365 => Actual nos of days / 365
  */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DayCountConvention {
     US30360,
     EU30360,
@@ -133,6 +136,23 @@ pub fn yrfrac((dt0, dt1): Period) -> f64 {
     yearfrac(dt0, dt1, US30360)
 }
 
+/** Check if a date is a business day, i.e. not a Saturday or Sunday
+- dt = date to be checked
+*/
+pub fn is_business_day(dt: NDt) -> bool {
+    !matches!(dt.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/** Following business day of a date, i.e. the date itself if a business day
+- dt = date to be adjusted
+*/
+pub fn following_business_day(mut dt: NDt) -> NDt {
+    while !is_business_day(dt) {
+        dt = dt.succ_opt().unwrap();
+    }
+    dt
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Currency {
     INR,
@@ -400,6 +420,156 @@ pub fn newt_raph(f: impl Fn(f64) -> f64, mut x: f64, xtol: f64) -> Option<f64> {
     None
 }
 
+/** Minimize a function of several variables using the Nelder-Mead method of argmin.
+The search is restarted from the best point found till it stops improving.
+- f    = function to be minimized
+- x0   = initial guess
+- step = size of the initial simplex along each variable
+*/
+pub fn minimize(f: impl Fn(&[f64]) -> f64, x0: &[f64], step: f64) -> Option<Vec<f64>> {
+    struct Cost<F: Fn(&[f64]) -> f64>(F);
+    impl<F: Fn(&[f64]) -> f64> CostFunction for Cost<F> {
+        type Param = Vec<f64>;
+        type Output = f64;
+        fn cost(&self, x: &Vec<f64>) -> Result<f64, argmin::core::Error> {
+            Ok((self.0)(x))
+        }
+    }
+
+    let cost = Cost(f);
+    let mut x = x0.to_vec();
+    let mut fx = (cost.0)(&x);
+    for _ in 0..10 {
+        let simplex = (0..=x.len())
+            .map(|i| {
+                let mut xi = x.clone();
+                if i > 0 {
+                    xi[i - 1] += step;
+                }
+                xi
+            })
+            .collect();
+        let solver = NelderMead::new(simplex).with_sd_tolerance(1e-14).ok()?;
+        let res = Executor::new(Cost(&cost.0), solver)
+            .configure(|st| st.max_iters(10_000))
+            .run()
+            .ok()?;
+        let xn = res.state().get_best_param()?.clone();
+        let fn_ = (cost.0)(&xn);
+        let done = fx - fn_ <= 1e-14 * fx.abs().max(1.0);
+        (x, fx) = (xn, fn_);
+        if done {
+            break;
+        }
+    }
+    Some(x)
+}
+
+/** Seeded generator of uniform and standard normal random numbers (SplitMix64 and Box-Muller),
+used by the simulations and the tests of the library
+*/
+pub(crate) struct Generator {
+    state: u64,
+    spare: Option<f64>,
+}
+
+impl Generator {
+    pub(crate) fn new(seed: u64) -> Self {
+        Generator {
+            state: seed,
+            spare: None,
+        }
+    }
+
+    /** Uniform random number in [0, 1) */
+    pub(crate) fn uniform(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        ((z ^ (z >> 31)) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /** Standard normal random number */
+    pub(crate) fn normal(&mut self) -> f64 {
+        if let Some(z) = self.spare.take() {
+            return z;
+        }
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        let r = (-2.0 * u1.ln()).sqrt();
+        let th = 2.0 * std::f64::consts::PI * u2;
+        self.spare = Some(r * th.sin());
+        r * th.cos()
+    }
+}
+
+/** Error in saving or loading data from files
+- Io     => Error in reading or writing the file
+- Format => Error in the format of the data, with its description
+*/
+#[derive(Debug)]
+pub enum FileError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Format(e) => write!(f, "format error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+impl From<std::io::Error> for FileError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/** Trait to save and load any serializable type, like curves and instruments, as RON or
+JSON files. It is implemented for all types which derive Serialize and Deserialize.
+*/
+pub trait FileFormats: Serialize + DeserializeOwned {
+    /** Save as RON file
+    - file = path of the file
+    */
+    fn to_ron_file(&self, file: &str) -> Result<(), FileError> {
+        let text = ron::to_string(self).map_err(|e| FileError::Format(e.to_string()))?;
+        Ok(std::fs::write(file, text)?)
+    }
+
+    /** Load from RON file
+    - file = path of the file
+    */
+    fn from_ron_file(file: &str) -> Result<Self, FileError> {
+        ron::from_str(&std::fs::read_to_string(file)?).map_err(|e| FileError::Format(e.to_string()))
+    }
+
+    /** Save as JSON file
+    - file = path of the file
+    */
+    fn to_json_file(&self, file: &str) -> Result<(), FileError> {
+        let text =
+            serde_json::to_string_pretty(self).map_err(|e| FileError::Format(e.to_string()))?;
+        Ok(std::fs::write(file, text)?)
+    }
+
+    /** Load from JSON file
+    - file = path of the file
+    */
+    fn from_json_file(file: &str) -> Result<Self, FileError> {
+        serde_json::from_str(&std::fs::read_to_string(file)?)
+            .map_err(|e| FileError::Format(e.to_string()))
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> FileFormats for T {}
+
 #[cfg(test)]
 mod base_fn {
     use super::*;
@@ -421,6 +591,15 @@ mod base_fn {
         assert_eq!(fwd_dis_fact((0.07, 1.0), (0.09, 3.0)), 0.8262363236653387);
     }
 
+    #[test]
+    fn business_days() {
+        assert_eq!(
+            following_business_day(NDt::from_ymd_opt(2024, 8, 17).unwrap()),
+            NDt::from_ymd_opt(2024, 8, 19).unwrap()
+        );
+        assert!(is_business_day(NDt::from_ymd_opt(2024, 8, 16).unwrap()));
+    }
+
     #[test]
     fn yearfrac_calc() {
         let dts = vec![
@@ -660,4 +839,15 @@ mod base_fn {
             Some(0.27845538159261773)
         );
     }
+
+    #[test]
+    fn minimize_calc() {
+        let xm = minimize(
+            |x| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2),
+            &[-1.2, 1.0],
+            0.5,
+        )
+        .unwrap();
+        assert!(approx(xm[0], 1.0) && approx(xm[1], 1.0));
+    }
 }